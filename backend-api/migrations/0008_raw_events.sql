@@ -0,0 +1,7 @@
+create table if not exists raw_events (
+  delivery_id text not null,
+  event_name text not null,
+  payload jsonb not null,
+  received_at timestamptz not null,
+  primary key (delivery_id, event_name)
+);
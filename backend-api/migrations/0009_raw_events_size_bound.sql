@@ -0,0 +1,6 @@
+alter table raw_events
+  add column if not exists payload_bytes int not null default 0,
+  add column if not exists truncated boolean not null default false;
+
+create index if not exists raw_events_received_at_idx
+  on raw_events (received_at);
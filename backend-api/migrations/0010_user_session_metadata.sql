@@ -0,0 +1,7 @@
+alter table user_sessions
+  add column if not exists user_agent text null,
+  add column if not exists ip_address text null;
+
+create index if not exists user_sessions_user_active_idx
+  on user_sessions (user_id, created_at desc)
+  where revoked_at is null;
@@ -0,0 +1,9 @@
+alter table wallet_link_challenges
+  add column if not exists purpose text not null default 'LINK';
+
+alter table wallet_link_challenges
+  drop constraint if exists wallet_link_challenges_purpose_check;
+
+alter table wallet_link_challenges
+  add constraint wallet_link_challenges_purpose_check
+  check (purpose in ('LINK', 'UNLINK'));
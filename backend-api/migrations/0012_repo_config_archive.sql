@@ -0,0 +1,2 @@
+alter table repo_configs
+  add column if not exists archived_at timestamptz null;
@@ -0,0 +1,3 @@
+alter table pr_challenges
+  add column if not exists confirm_failed_attempts integer not null default 0,
+  add column if not exists confirm_locked_until timestamptz null;
@@ -0,0 +1,2 @@
+alter table wallet_links
+  add column if not exists ens_name text null;
@@ -0,0 +1,5 @@
+create index if not exists pr_challenges_repo_author_login_idx
+  on pr_challenges (github_repo_id, lower(github_pr_author_login), created_at desc);
+
+create index if not exists pr_challenges_repo_author_id_idx
+  on pr_challenges (github_repo_id, github_pr_author_id, created_at desc);
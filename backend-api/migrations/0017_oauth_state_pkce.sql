@@ -0,0 +1,2 @@
+alter table oauth_states
+  add column if not exists code_verifier text null;
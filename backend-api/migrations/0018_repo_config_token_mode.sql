@@ -0,0 +1,11 @@
+alter table repo_configs
+  drop constraint if exists repo_configs_input_mode_check;
+
+alter table repo_configs
+  add constraint repo_configs_input_mode_check check (input_mode in ('ETH', 'USD', 'TOKEN'));
+
+-- TOKEN thresholds are entered in token units and never priced, so they carry no spot quote.
+alter table repo_configs
+  alter column spot_price_usd drop not null,
+  alter column spot_source drop not null,
+  alter column spot_at drop not null;
@@ -0,0 +1,15 @@
+create table if not exists outbound_events (
+  id uuid primary key,
+  event_type text not null check (event_type in ('WALLET_LINKED', 'WALLET_UNLINKED')),
+  payload jsonb not null,
+  status text not null check (status in ('PENDING', 'DELIVERED', 'FAILED')),
+  attempts int not null default 0,
+  next_attempt_at timestamptz not null,
+  last_error text null,
+  delivered_at timestamptz null,
+  created_at timestamptz not null
+);
+
+create index if not exists outbound_events_due
+  on outbound_events (next_attempt_at)
+  where status = 'PENDING';
@@ -0,0 +1,3 @@
+create index if not exists pr_challenges_pending_repo_idx
+  on pr_challenges (github_repo_id)
+  where status = 'PENDING';
@@ -0,0 +1,7 @@
+-- Org stake pooling: any of these users' staked wallets can satisfy a PR author's threshold.
+alter table repo_configs
+  add column if not exists pooled_stake_github_user_ids bigint[] not null default '{}';
+
+-- Who satisfied the stake check: the author, or the pool member whose stake was used.
+alter table pr_challenges
+  add column if not exists stake_satisfied_by_github_user_id bigint null;
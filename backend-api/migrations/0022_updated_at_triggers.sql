@@ -0,0 +1,33 @@
+-- updated_at is maintained by the database: every UPDATE (including the update branch of an
+-- upsert) stamps the row with the transaction time, so write paths cannot forget or skew it.
+create or replace function set_updated_at() returns trigger as $$
+begin
+  new.updated_at := now();
+  return new;
+end;
+$$ language plpgsql;
+
+alter table repo_configs alter column updated_at set default now();
+alter table bot_actions alter column updated_at set default now();
+alter table pr_challenges alter column updated_at set default now();
+alter table github_installations alter column updated_at set default now();
+
+drop trigger if exists repo_configs_set_updated_at on repo_configs;
+create trigger repo_configs_set_updated_at
+  before update on repo_configs
+  for each row execute function set_updated_at();
+
+drop trigger if exists bot_actions_set_updated_at on bot_actions;
+create trigger bot_actions_set_updated_at
+  before update on bot_actions
+  for each row execute function set_updated_at();
+
+drop trigger if exists pr_challenges_set_updated_at on pr_challenges;
+create trigger pr_challenges_set_updated_at
+  before update on pr_challenges
+  for each row execute function set_updated_at();
+
+drop trigger if exists github_installations_set_updated_at on github_installations;
+create trigger github_installations_set_updated_at
+  before update on github_installations
+  for each row execute function set_updated_at();
@@ -0,0 +1,14 @@
+alter table repo_configs
+  drop constraint if exists repo_configs_input_mode_check;
+
+alter table repo_configs
+  add constraint repo_configs_input_mode_check check (input_mode in ('ETH', 'USD', 'TOKEN', 'FIAT'));
+
+-- FIAT thresholds are priced against an `ETH_{input_currency}` quote; spot_price_usd then holds
+-- that quote's price in input_currency.
+alter table repo_configs
+  add column if not exists input_currency text null
+    check (input_currency in ('USD', 'EUR', 'GBP'));
+
+create index if not exists spot_quotes_pair_latest
+  on spot_quotes (pair, fetched_at desc);
@@ -0,0 +1,19 @@
+alter table wallet_link_challenges
+  drop constraint if exists wallet_link_challenges_purpose_check;
+
+alter table wallet_link_challenges
+  add constraint wallet_link_challenges_purpose_check
+  check (purpose in ('LINK', 'UNLINK', 'DELEGATE', 'RECOVERY'));
+
+create table if not exists wallet_recovery_links (
+  id uuid primary key,
+  user_id uuid not null references users(id),
+  recovery_wallet_address text not null,
+  linked_wallet_address text not null,
+  created_at timestamptz not null,
+  revoked_at timestamptz null
+);
+
+create unique index if not exists wallet_recovery_links_active_user_idx
+  on wallet_recovery_links (user_id)
+  where revoked_at is null;
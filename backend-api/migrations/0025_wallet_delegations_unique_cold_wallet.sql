@@ -0,0 +1,15 @@
+-- A cold wallet backs at most one account. Older duplicate delegations are revoked so the
+-- newest one per cold wallet survives.
+update wallet_delegations d
+set revoked_at = now()
+where d.revoked_at is null
+  and exists (
+    select 1 from wallet_delegations newer
+    where newer.revoked_at is null
+      and lower(newer.cold_wallet_address) = lower(d.cold_wallet_address)
+      and (newer.created_at, newer.id) > (d.created_at, d.id)
+  );
+
+create unique index if not exists wallet_delegations_active_cold_wallet_idx
+  on wallet_delegations (lower(cold_wallet_address))
+  where revoked_at is null;
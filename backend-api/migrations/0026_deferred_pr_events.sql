@@ -0,0 +1,9 @@
+-- PR events ignored because their repo was at the pending challenge cap. The deadline sweeper
+-- replays them once the repo is back under the cap.
+create table if not exists deferred_pr_events (
+  github_repo_id bigint not null,
+  github_pr_number int not null,
+  payload jsonb not null,
+  deferred_at timestamptz not null,
+  primary key (github_repo_id, github_pr_number)
+);
@@ -1,10 +1,12 @@
-use sqlx::PgPool;
+use std::{str::FromStr, time::Duration};
+
+use sqlx::{ConnectOptions, PgPool, postgres::PgConnectOptions};
 
 use crate::{
     config::Config,
     services::{
-        github_oauth::GithubOAuthService, quote_service::QuoteService, rate_limiter::RateLimiter,
-        stake_service::StakeService,
+        github_oauth::GithubOAuthService, identity_service::IdentityService,
+        quote_service::QuoteService, rate_limiter::RateLimiter, stake_service::StakeService,
     },
 };
 
@@ -14,22 +16,107 @@ pub struct AppState {
     pub quote_service: QuoteService,
     pub github_oauth_service: GithubOAuthService,
     pub stake_service: StakeService,
+    pub identity_service: IdentityService,
     pub rate_limiter: RateLimiter,
 }
 
 impl AppState {
     pub fn new(pool: PgPool, config: Config) -> Self {
-        let quote_service = QuoteService::new(pool.clone());
-        let github_oauth_service = GithubOAuthService::new();
+        let quote_service = QuoteService::new(pool.clone(), &config);
+        let github_oauth_service = GithubOAuthService::new(&config);
         let stake_service = StakeService::new(&config);
-        let rate_limiter = RateLimiter::new();
+        let identity_service = IdentityService::new(&config);
+        let rate_limiter = RateLimiter::from_redis_url(config.redis_url.as_deref())
+            .expect("REDIS_URL is validated when config is loaded");
         Self {
             pool,
             config,
             quote_service,
             github_oauth_service,
             stake_service,
+            identity_service,
             rate_limiter,
         }
     }
 }
+
+/// Connection options for the main pool. Statements slower than `SLOW_QUERY_THRESHOLD_MS` are
+/// logged at WARN on the `sqlx::query` target with their leading words; `0` turns that off.
+pub fn pg_connect_options(config: &Config) -> Result<PgConnectOptions, sqlx::Error> {
+    let options = PgConnectOptions::from_str(&config.database_url)?;
+    Ok(match config.slow_query_threshold_ms {
+        0 => options.log_slow_statements(log::LevelFilter::Off, Duration::ZERO),
+        threshold_ms => {
+            options.log_slow_statements(log::LevelFilter::Warn, Duration::from_millis(threshold_ms))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::PgPoolOptions;
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().expect("logs lock").extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL postgres"]
+    async fn logs_statements_slower_than_the_threshold() {
+        let Ok(database_url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let sleep_with_threshold = |slow_query_threshold_ms| {
+            let config = Config {
+                database_url: database_url.clone(),
+                slow_query_threshold_ms,
+                ..Config::for_tests()
+            };
+            async move {
+                let pool = PgPoolOptions::new()
+                    .max_connections(1)
+                    .connect_with(pg_connect_options(&config).expect("connect options"))
+                    .await
+                    .expect("pool");
+                sqlx::query("select pg_sleep(0.1)")
+                    .execute(&pool)
+                    .await
+                    .expect("sleep");
+            }
+        };
+
+        sleep_with_threshold(20).await;
+        let output = String::from_utf8(logs.0.lock().expect("logs lock").clone()).expect("utf8");
+        assert!(output.contains("WARN"), "{output}");
+        assert!(output.contains("slow statement"), "{output}");
+        assert!(output.contains("select pg_sleep(0.1)"), "{output}");
+
+        logs.0.lock().expect("logs lock").clear();
+        sleep_with_threshold(0).await;
+        let output = String::from_utf8(logs.0.lock().expect("logs lock").clone()).expect("utf8");
+        assert!(!output.contains("slow statement"), "{output}");
+    }
+}
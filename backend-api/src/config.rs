@@ -1,4 +1,143 @@
 use std::env;
+use std::net::IpAddr;
+
+use thiserror::Error;
+
+/// OAuth scopes the backend knows how to work with; anything else is rejected at startup.
+const KNOWN_GITHUB_OAUTH_SCOPES: &[&str] =
+    &["read:user", "user:email", "public_repo", "repo", "read:org"];
+/// Events integrators can subscribe to with `OUTBOUND_WEBHOOK_EVENTS`.
+pub const OUTBOUND_WEBHOOK_EVENT_TYPES: &[&str] = &["WALLET_LINKED", "WALLET_UNLINKED"];
+const DEFAULT_GITHUB_OAUTH_SCOPES: &str = "read:user public_repo";
+const DEFAULT_GITHUB_USER_AGENT: &str = "sitg-backend";
+/// REST API version pinned via `X-GitHub-Api-Version` unless `GITHUB_API_VERSION` overrides it.
+const DEFAULT_GITHUB_API_VERSION: &str = "2022-11-28";
+/// Chain wallet links, typed-data domains and stake lookups are bound to unless `CHAIN_ID`
+/// overrides it (Base mainnet; Base Sepolia is 84532).
+pub const DEFAULT_CHAIN_ID: u64 = 8453;
+/// EIP-712 domain `version` typed data is issued with unless `TYPED_DATA_DOMAIN_VERSION`
+/// overrides it. Bump it whenever the signed message schema changes.
+const DEFAULT_TYPED_DATA_DOMAIN_VERSION: &str = "1";
+/// Shortest accepted alphanumeric tokens (~95 and ~190 bits); shorter ones fail startup.
+const MIN_GATE_TOKEN_LENGTH: usize = 16;
+const MIN_SESSION_TOKEN_LENGTH: usize = 32;
+const MAX_TOKEN_LENGTH: usize = 256;
+/// GitHub rejects issue comment bodies longer than this many characters.
+const GITHUB_COMMENT_MAX_CHARS: usize = 65_536;
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error(transparent)]
+    Env(#[from] env::VarError),
+    #[error("{name} is invalid: {reason}")]
+    Invalid { name: &'static str, reason: String },
+}
+
+/// What the retention job does with `audit_events` older than the retention window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuditRetentionMode {
+    Delete,
+    /// Keep the rows but replace logins and wallet addresses in their payloads with keyed hashes.
+    Anonymize,
+}
+
+/// How a live ETH/USD price is chosen from the configured price sources.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuoteStrategy {
+    /// Try sources in order and use the first price returned.
+    FirstSuccess,
+    /// Query every source at once and use the median of at least two prices.
+    Median,
+    /// Query every source at once and use the first positive price, cancelling the rest.
+    Fastest,
+}
+
+/// Whether stake must also be time-locked to count toward a threshold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StakeLockMode {
+    /// Stake counts only while `unlockTime(address)` is in the future.
+    Required,
+    /// The contract has no time-lock (no `unlockTime`); only the balance is checked.
+    Disabled,
+}
+
+/// How a wallet's stake is read from the staking contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StakePositionMode {
+    /// One position per wallet via `stakedBalance(address)` and `unlockTime(address)`.
+    Single,
+    /// Lock positions enumerated via `stakeCount(address)` and `stakeAt(address, uint256)`.
+    Positions,
+}
+
+/// At most `max` requests per key over any trailing `window_secs`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    pub max: u32,
+    pub window_secs: i64,
+}
+
+/// Budgets for every rate-limited endpoint. Each one reads `RL_<NAME>_MAX` and
+/// `RL_<NAME>_WINDOW_SECONDS`; missing or non-positive values keep the default.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// `RL_AUTH_START`: OAuth starts across all clients.
+    pub auth_start: RateLimit,
+    /// `RL_AUTH_CALLBACK_IP`: OAuth callbacks per client IP.
+    pub auth_callback_ip: RateLimit,
+    /// `RL_AUTH_CALLBACK`: OAuth callbacks across all clients.
+    pub auth_callback: RateLimit,
+    /// `RL_WALLET_CHALLENGE`: wallet challenge lookups per user.
+    pub wallet_challenge: RateLimit,
+    /// `RL_WALLET_CONFIRM`: confirmation typed-data requests per user.
+    pub wallet_confirm: RateLimit,
+    /// `RL_GATE`: gate page loads per client IP; slows gate token enumeration.
+    pub gate: RateLimit,
+    /// `RL_GATE_VERIFIED`: token-less verified and PR status checks per client IP, which CI
+    /// may poll.
+    pub gate_verified: RateLimit,
+    /// `RL_QUOTE`: ETH/USD quote reads across all clients; each miss may hit the price sources.
+    pub quote: RateLimit,
+    /// `RL_STAKE_BATCH`: batch stake status reads per client IP; each one fans out to RPC.
+    pub stake_batch: RateLimit,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        let per_minute = |max| RateLimit {
+            max,
+            window_secs: 60,
+        };
+        Self {
+            auth_start: per_minute(100),
+            auth_callback_ip: per_minute(20),
+            auth_callback: per_minute(100),
+            wallet_challenge: per_minute(20),
+            wallet_confirm: per_minute(30),
+            gate: per_minute(60),
+            gate_verified: per_minute(60),
+            quote: per_minute(120),
+            stake_batch: per_minute(10),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            auth_start: parse_rate_limit("AUTH_START", defaults.auth_start),
+            auth_callback_ip: parse_rate_limit("AUTH_CALLBACK_IP", defaults.auth_callback_ip),
+            auth_callback: parse_rate_limit("AUTH_CALLBACK", defaults.auth_callback),
+            wallet_challenge: parse_rate_limit("WALLET_CHALLENGE", defaults.wallet_challenge),
+            wallet_confirm: parse_rate_limit("WALLET_CONFIRM", defaults.wallet_confirm),
+            gate: parse_rate_limit("GATE", defaults.gate),
+            gate_verified: parse_rate_limit("GATE_VERIFIED", defaults.gate_verified),
+            quote: parse_rate_limit("QUOTE", defaults.quote),
+            stake_batch: parse_rate_limit("STAKE_BATCH", defaults.stake_batch),
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Config {
@@ -11,13 +150,64 @@ pub struct Config {
     pub github_client_id: Option<String>,
     pub github_client_secret: Option<String>,
     pub session_cookie_name: String,
+    pub keep_sessions_on_login: bool,
     pub blocked_unlink_wallets: Vec<String>,
-    pub base_rpc_url: Option<String>,
+    /// Base RPC endpoints in failover order.
+    pub base_rpc_urls: Vec<String>,
     pub staking_contract_address: Option<String>,
+    pub admin_github_user_ids: Vec<i64>,
+    pub trust_proxy_headers: bool,
+    pub wallet_unlink_reauth_required: bool,
+    pub github_oauth_scopes: Vec<String>,
+    pub github_oauth_pkce: bool,
+    pub github_user_agent: String,
+    pub github_api_version: String,
+    pub bot_claim_max_per_request: i64,
+    pub gate_confirm_max_attempts: i32,
+    pub gate_confirm_lockout_seconds: i64,
+    pub audit_retention_mode: AuditRetentionMode,
+    pub audit_anonymize_key: Option<String>,
+    pub maintenance_mode: bool,
+    pub quote_stale_while_revalidate_seconds: i64,
+    pub ens_reverse_resolver_address: Option<String>,
+    pub ens_reverse_namespace: String,
+    pub wallet_identity_required: bool,
+    pub github_delivery_dedupe_window_seconds: i64,
+    pub stake_token_decimals: u32,
+    pub timeout_close_comment: Option<String>,
+    pub outbound_webhook_url: Option<String>,
+    pub outbound_webhook_secret: Option<String>,
+    pub outbound_webhook_events: Vec<String>,
+    pub max_pending_challenges_per_repo: i64,
+    pub job_jitter_percent: u32,
+    pub comment_max_chars: usize,
+    pub gate_token_length: usize,
+    pub session_token_length: usize,
+    pub bot_claim_max_response_bytes: i64,
+    pub redis_url: Option<String>,
+    pub http_pool_max_idle_per_host: Option<usize>,
+    pub http_pool_idle_timeout_seconds: u64,
+    pub rate_limits: RateLimitConfig,
+    pub quote_cache_ttl_secs: i64,
+    pub quote_max_staleness_secs: i64,
+    pub quote_strategy: QuoteStrategy,
+    pub rate_limit_bypass_ips: Vec<IpAddr>,
+    pub rate_limit_bypass_keys: Vec<String>,
+    pub typed_data_domain_version: String,
+    pub typed_data_accepted_versions: Vec<String>,
+    pub quote_breaker_failure_threshold: u32,
+    pub quote_breaker_cooldown_secs: u64,
+    pub stake_lock_mode: StakeLockMode,
+    pub multicall3_address: Option<String>,
+    pub stake_status_cache_ttl_secs: u64,
+    pub stake_position_mode: StakePositionMode,
+    pub slow_query_threshold_ms: u64,
+    pub chain_id: u64,
+    pub stake_token_address: Option<String>,
 }
 
 impl Config {
-    pub fn from_env() -> Result<Self, env::VarError> {
+    pub fn from_env() -> Result<Self, ConfigError> {
         let host = env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
         let port = env::var("PORT")
             .ok()
@@ -35,6 +225,7 @@ impl Config {
         let github_client_secret = env::var("GITHUB_CLIENT_SECRET").ok();
         let session_cookie_name =
             env::var("SESSION_COOKIE_NAME").unwrap_or_else(|_| "sitg_session".to_string());
+        let keep_sessions_on_login = env_flag("KEEP_SESSIONS_ON_LOGIN");
         let blocked_unlink_wallets = env::var("BLOCKED_UNLINK_WALLETS")
             .unwrap_or_default()
             .split(',')
@@ -42,8 +233,202 @@ impl Config {
             .filter(|s| !s.is_empty())
             .map(str::to_lowercase)
             .collect::<Vec<_>>();
-        let base_rpc_url = env::var("BASE_RPC_URL").ok();
+        let base_rpc_urls = parse_base_rpc_urls(&env::var("BASE_RPC_URL").unwrap_or_default());
         let staking_contract_address = env::var("STAKING_CONTRACT_ADDRESS").ok();
+        let admin_github_user_ids = env::var("ADMIN_GITHUB_USER_IDS")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|s| s.trim().parse::<i64>().ok())
+            .collect::<Vec<_>>();
+        let trust_proxy_headers = env_flag("TRUST_PROXY_HEADERS");
+        let wallet_unlink_reauth_required = env_flag("WALLET_UNLINK_REAUTH_REQUIRED");
+        let github_oauth_scopes = parse_github_oauth_scopes(
+            &env::var("GITHUB_OAUTH_SCOPES")
+                .unwrap_or_else(|_| DEFAULT_GITHUB_OAUTH_SCOPES.to_string()),
+        )?;
+        let github_oauth_pkce = env_flag("GITHUB_OAUTH_PKCE");
+        let github_user_agent = header_safe_env("GITHUB_USER_AGENT", DEFAULT_GITHUB_USER_AGENT)?;
+        let github_api_version = header_safe_env("GITHUB_API_VERSION", DEFAULT_GITHUB_API_VERSION)?;
+        let bot_claim_max_per_request = env::var("BOT_CLAIM_MAX_PER_REQUEST")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(100);
+        let gate_confirm_max_attempts = env::var("GATE_CONFIRM_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|v| v.parse::<i32>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(5);
+        let gate_confirm_lockout_seconds = env::var("GATE_CONFIRM_LOCKOUT_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(900);
+        let audit_retention_mode = parse_audit_retention_mode(
+            &env::var("AUDIT_RETENTION_MODE").unwrap_or_else(|_| "delete".to_string()),
+        )?;
+        let audit_anonymize_key = env::var("AUDIT_ANONYMIZE_KEY")
+            .ok()
+            .filter(|v| !v.is_empty());
+        if audit_retention_mode == AuditRetentionMode::Anonymize && audit_anonymize_key.is_none() {
+            return Err(ConfigError::Invalid {
+                name: "AUDIT_ANONYMIZE_KEY",
+                reason: "required when AUDIT_RETENTION_MODE is anonymize".to_string(),
+            });
+        }
+        let maintenance_mode = env_flag("MAINTENANCE_MODE");
+        let quote_stale_while_revalidate_seconds = env::var("QUOTE_STALE_WHILE_REVALIDATE_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v >= 0)
+            .unwrap_or(0);
+        let ens_reverse_resolver_address = env::var("ENS_REVERSE_RESOLVER_ADDRESS").ok();
+        let ens_reverse_namespace =
+            env::var("ENS_REVERSE_NAMESPACE").unwrap_or_else(|_| "addr.reverse".to_string());
+        let wallet_identity_required = env_flag("WALLET_IDENTITY_REQUIRED");
+        let github_delivery_dedupe_window_seconds =
+            env::var("GITHUB_DELIVERY_DEDUPE_WINDOW_SECONDS")
+                .ok()
+                .and_then(|v| v.parse::<i64>().ok())
+                .filter(|v| *v >= 0)
+                .unwrap_or(0);
+        let stake_token_decimals =
+            parse_stake_token_decimals(env::var("STAKE_TOKEN_DECIMALS").ok())?;
+        let timeout_close_comment = env::var("TIMEOUT_CLOSE_COMMENT")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        let outbound_webhook_url = env::var("OUTBOUND_WEBHOOK_URL")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        let outbound_webhook_secret = env::var("OUTBOUND_WEBHOOK_SECRET")
+            .ok()
+            .filter(|v| !v.is_empty());
+        if outbound_webhook_url.is_some() && outbound_webhook_secret.is_none() {
+            return Err(ConfigError::Invalid {
+                name: "OUTBOUND_WEBHOOK_SECRET",
+                reason: "required when OUTBOUND_WEBHOOK_URL is set".to_string(),
+            });
+        }
+        let outbound_webhook_events = parse_outbound_webhook_events(
+            &env::var("OUTBOUND_WEBHOOK_EVENTS").unwrap_or_default(),
+        )?;
+        let max_pending_challenges_per_repo = env::var("MAX_PENDING_CHALLENGES_PER_REPO")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(200);
+        let job_jitter_percent = env::var("JOB_JITTER_PERCENT")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|v| *v <= 50)
+            .unwrap_or(10);
+        let comment_max_chars = env::var("COMMENT_MAX_CHARS")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|v| (1..=GITHUB_COMMENT_MAX_CHARS).contains(v))
+            .unwrap_or(65_000);
+        let gate_token_length = parse_token_length(
+            "GATE_TOKEN_LENGTH",
+            env::var("GATE_TOKEN_LENGTH").ok(),
+            24,
+            MIN_GATE_TOKEN_LENGTH,
+        )?;
+        let session_token_length = parse_token_length(
+            "SESSION_TOKEN_LENGTH",
+            env::var("SESSION_TOKEN_LENGTH").ok(),
+            64,
+            MIN_SESSION_TOKEN_LENGTH,
+        )?;
+        let bot_claim_max_response_bytes = env::var("BOT_CLAIM_MAX_RESPONSE_BYTES")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(1_048_576);
+        let redis_url = env::var("REDIS_URL")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        if let Some(url) = redis_url.as_deref() {
+            redis::IntoConnectionInfo::into_connection_info(url).map_err(|err| {
+                ConfigError::Invalid {
+                    name: "REDIS_URL",
+                    reason: err.to_string(),
+                }
+            })?;
+        }
+        let http_pool_max_idle_per_host = env::var("HTTP_POOL_MAX_IDLE_PER_HOST")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok());
+        let http_pool_idle_timeout_seconds = env::var("HTTP_POOL_IDLE_TIMEOUT_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(90);
+        let rate_limits = RateLimitConfig::from_env();
+        let quote_cache_ttl_secs = env::var("QUOTE_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(300);
+        let quote_max_staleness_secs = env::var("QUOTE_MAX_STALENESS_SECS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v >= 0)
+            .unwrap_or(3600);
+        let quote_strategy = parse_quote_strategy(
+            &env::var("QUOTE_STRATEGY").unwrap_or_else(|_| "first_success".to_string()),
+        )?;
+        let rate_limit_bypass_ips =
+            parse_rate_limit_bypass_ips(&env::var("RATE_LIMIT_BYPASS_IPS").unwrap_or_default())?;
+        let rate_limit_bypass_keys = env::var("RATE_LIMIT_BYPASS_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let typed_data_domain_version = env::var("TYPED_DATA_DOMAIN_VERSION")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| DEFAULT_TYPED_DATA_DOMAIN_VERSION.to_string());
+        let typed_data_accepted_versions = parse_typed_data_accepted_versions(
+            &typed_data_domain_version,
+            &env::var("TYPED_DATA_ACCEPTED_VERSIONS").unwrap_or_default(),
+        );
+        let quote_breaker_failure_threshold = env::var("QUOTE_BREAKER_FAILURE_THRESHOLD")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(3);
+        let quote_breaker_cooldown_secs = env::var("QUOTE_BREAKER_COOLDOWN_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(30);
+        let stake_lock_mode = parse_stake_lock_mode(
+            &env::var("STAKE_LOCK_MODE").unwrap_or_else(|_| "required".to_string()),
+        )?;
+        let multicall3_address = env::var("MULTICALL3_ADDRESS")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        let stake_status_cache_ttl_secs = env::var("STAKE_STATUS_CACHE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(0);
+        let stake_position_mode = parse_stake_position_mode(
+            &env::var("STAKE_POSITION_MODE").unwrap_or_else(|_| "single".to_string()),
+        )?;
+        let slow_query_threshold_ms = env::var("SLOW_QUERY_THRESHOLD_MS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(1000);
+        let chain_id = parse_chain_id(env::var("CHAIN_ID").ok().as_deref())?;
+        let stake_token_address = env::var("STAKE_TOKEN_ADDRESS")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
         let database_url = env::var("DATABASE_URL")?;
 
         Ok(Self {
@@ -56,11 +441,349 @@ impl Config {
             github_client_id,
             github_client_secret,
             session_cookie_name,
+            keep_sessions_on_login,
             blocked_unlink_wallets,
-            base_rpc_url,
+            base_rpc_urls,
             staking_contract_address,
+            admin_github_user_ids,
+            trust_proxy_headers,
+            wallet_unlink_reauth_required,
+            github_oauth_scopes,
+            github_oauth_pkce,
+            github_user_agent,
+            github_api_version,
+            bot_claim_max_per_request,
+            gate_confirm_max_attempts,
+            gate_confirm_lockout_seconds,
+            audit_retention_mode,
+            audit_anonymize_key,
+            maintenance_mode,
+            quote_stale_while_revalidate_seconds,
+            ens_reverse_resolver_address,
+            ens_reverse_namespace,
+            wallet_identity_required,
+            github_delivery_dedupe_window_seconds,
+            stake_token_decimals,
+            timeout_close_comment,
+            outbound_webhook_url,
+            outbound_webhook_secret,
+            outbound_webhook_events,
+            max_pending_challenges_per_repo,
+            job_jitter_percent,
+            comment_max_chars,
+            gate_token_length,
+            session_token_length,
+            bot_claim_max_response_bytes,
+            redis_url,
+            http_pool_max_idle_per_host,
+            http_pool_idle_timeout_seconds,
+            rate_limits,
+            quote_cache_ttl_secs,
+            quote_max_staleness_secs,
+            quote_strategy,
+            rate_limit_bypass_ips,
+            rate_limit_bypass_keys,
+            typed_data_domain_version,
+            typed_data_accepted_versions,
+            quote_breaker_failure_threshold,
+            quote_breaker_cooldown_secs,
+            stake_lock_mode,
+            multicall3_address,
+            stake_status_cache_ttl_secs,
+            stake_position_mode,
+            slow_query_threshold_ms,
+            chain_id,
+            stake_token_address,
         })
     }
+
+    #[cfg(test)]
+    pub fn for_tests() -> Self {
+        Self {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+            database_url: "postgres://localhost/sitg".to_string(),
+            db_max_connections: 10,
+            app_base_url: "https://sitg.io".to_string(),
+            api_base_url: "http://localhost:8080".to_string(),
+            github_client_id: None,
+            github_client_secret: None,
+            session_cookie_name: "sitg_session".to_string(),
+            keep_sessions_on_login: false,
+            blocked_unlink_wallets: vec![],
+            base_rpc_urls: Vec::new(),
+            staking_contract_address: None,
+            admin_github_user_ids: vec![],
+            trust_proxy_headers: false,
+            wallet_unlink_reauth_required: false,
+            github_oauth_scopes: vec!["read:user".to_string(), "public_repo".to_string()],
+            github_oauth_pkce: false,
+            github_user_agent: DEFAULT_GITHUB_USER_AGENT.to_string(),
+            github_api_version: DEFAULT_GITHUB_API_VERSION.to_string(),
+            bot_claim_max_per_request: 100,
+            gate_confirm_max_attempts: 5,
+            gate_confirm_lockout_seconds: 900,
+            audit_retention_mode: AuditRetentionMode::Delete,
+            audit_anonymize_key: None,
+            maintenance_mode: false,
+            quote_stale_while_revalidate_seconds: 0,
+            ens_reverse_resolver_address: None,
+            ens_reverse_namespace: "addr.reverse".to_string(),
+            wallet_identity_required: false,
+            github_delivery_dedupe_window_seconds: 0,
+            stake_token_decimals: 18,
+            timeout_close_comment: None,
+            outbound_webhook_url: None,
+            outbound_webhook_secret: None,
+            outbound_webhook_events: vec![],
+            max_pending_challenges_per_repo: 200,
+            job_jitter_percent: 10,
+            comment_max_chars: 65_000,
+            gate_token_length: 24,
+            session_token_length: 64,
+            bot_claim_max_response_bytes: 1_048_576,
+            redis_url: None,
+            http_pool_max_idle_per_host: None,
+            http_pool_idle_timeout_seconds: 90,
+            rate_limits: RateLimitConfig::default(),
+            quote_cache_ttl_secs: 300,
+            quote_max_staleness_secs: 3600,
+            quote_strategy: QuoteStrategy::FirstSuccess,
+            rate_limit_bypass_ips: Vec::new(),
+            rate_limit_bypass_keys: Vec::new(),
+            typed_data_domain_version: DEFAULT_TYPED_DATA_DOMAIN_VERSION.to_string(),
+            typed_data_accepted_versions: vec![DEFAULT_TYPED_DATA_DOMAIN_VERSION.to_string()],
+            quote_breaker_failure_threshold: 3,
+            quote_breaker_cooldown_secs: 30,
+            stake_lock_mode: StakeLockMode::Required,
+            multicall3_address: None,
+            stake_status_cache_ttl_secs: 0,
+            stake_position_mode: StakePositionMode::Single,
+            slow_query_threshold_ms: 1000,
+            chain_id: DEFAULT_CHAIN_ID,
+            stake_token_address: None,
+        }
+    }
+}
+
+/// Accepts comma- or space-separated scopes, deduped in order.
+fn parse_github_oauth_scopes(raw: &str) -> Result<Vec<String>, ConfigError> {
+    let mut scopes: Vec<String> = Vec::new();
+    for scope in raw
+        .split([',', ' '])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        if !KNOWN_GITHUB_OAUTH_SCOPES.contains(&scope) {
+            return Err(ConfigError::Invalid {
+                name: "GITHUB_OAUTH_SCOPES",
+                reason: format!("unknown scope `{scope}`"),
+            });
+        }
+        if !scopes.iter().any(|s| s == scope) {
+            scopes.push(scope.to_string());
+        }
+    }
+    if scopes.is_empty() {
+        return Err(ConfigError::Invalid {
+            name: "GITHUB_OAUTH_SCOPES",
+            reason: "at least one scope is required".to_string(),
+        });
+    }
+    Ok(scopes)
+}
+
+/// Comma-separated endpoints, tried in the order given.
+fn parse_base_rpc_urls(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Comma-separated IP addresses; a malformed entry fails startup rather than silently
+/// leaving a trusted monitor rate limited.
+fn parse_rate_limit_bypass_ips(raw: &str) -> Result<Vec<IpAddr>, ConfigError> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|ip| {
+            ip.parse::<IpAddr>().map_err(|_| ConfigError::Invalid {
+                name: "RATE_LIMIT_BYPASS_IPS",
+                reason: format!("`{ip}` is not an IP address"),
+            })
+        })
+        .collect()
+}
+
+/// The current version first, then comma-separated prior versions still verified while
+/// signatures issued before a bump drain; duplicates are dropped.
+fn parse_typed_data_accepted_versions(current: &str, raw: &str) -> Vec<String> {
+    let mut versions = vec![current.to_string()];
+    for version in raw.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+        if !versions.iter().any(|v| v == version) {
+            versions.push(version.to_string());
+        }
+    }
+    versions
+}
+
+/// Comma-separated event types; empty means no events are delivered.
+fn parse_outbound_webhook_events(raw: &str) -> Result<Vec<String>, ConfigError> {
+    let mut events: Vec<String> = Vec::new();
+    for event in raw
+        .split(',')
+        .map(|e| e.trim().to_uppercase())
+        .filter(|e| !e.is_empty())
+    {
+        if !OUTBOUND_WEBHOOK_EVENT_TYPES.contains(&event.as_str()) {
+            return Err(ConfigError::Invalid {
+                name: "OUTBOUND_WEBHOOK_EVENTS",
+                reason: format!("unknown event `{event}`"),
+            });
+        }
+        if !events.contains(&event) {
+            events.push(event);
+        }
+    }
+    // Unset subscribes to everything, so setting only the URL is enough.
+    if events.is_empty() {
+        events = OUTBOUND_WEBHOOK_EVENT_TYPES
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+    }
+    Ok(events)
+}
+
+fn parse_quote_strategy(raw: &str) -> Result<QuoteStrategy, ConfigError> {
+    match raw.trim().to_lowercase().as_str() {
+        "first_success" => Ok(QuoteStrategy::FirstSuccess),
+        "median" => Ok(QuoteStrategy::Median),
+        "fastest" => Ok(QuoteStrategy::Fastest),
+        other => Err(ConfigError::Invalid {
+            name: "QUOTE_STRATEGY",
+            reason: format!("expected `first_success`, `median` or `fastest`, got `{other}`"),
+        }),
+    }
+}
+
+/// A typo must not silently fall back to mainnet, so anything but a positive integer fails.
+fn parse_chain_id(raw: Option<&str>) -> Result<u64, ConfigError> {
+    match raw.map(str::trim).filter(|raw| !raw.is_empty()) {
+        None => Ok(DEFAULT_CHAIN_ID),
+        Some(raw) => raw
+            .parse::<u64>()
+            .ok()
+            .filter(|chain_id| *chain_id > 0)
+            .ok_or_else(|| ConfigError::Invalid {
+                name: "CHAIN_ID",
+                reason: format!("expected a positive integer, got `{raw}`"),
+            }),
+    }
+}
+
+fn parse_stake_lock_mode(raw: &str) -> Result<StakeLockMode, ConfigError> {
+    match raw.trim().to_lowercase().as_str() {
+        "required" => Ok(StakeLockMode::Required),
+        "disabled" => Ok(StakeLockMode::Disabled),
+        other => Err(ConfigError::Invalid {
+            name: "STAKE_LOCK_MODE",
+            reason: format!("expected `required` or `disabled`, got `{other}`"),
+        }),
+    }
+}
+
+fn parse_stake_position_mode(raw: &str) -> Result<StakePositionMode, ConfigError> {
+    match raw.trim().to_lowercase().as_str() {
+        "single" => Ok(StakePositionMode::Single),
+        "positions" => Ok(StakePositionMode::Positions),
+        other => Err(ConfigError::Invalid {
+            name: "STAKE_POSITION_MODE",
+            reason: format!("expected `single` or `positions`, got `{other}`"),
+        }),
+    }
+}
+
+fn parse_audit_retention_mode(raw: &str) -> Result<AuditRetentionMode, ConfigError> {
+    match raw.trim().to_lowercase().as_str() {
+        "delete" => Ok(AuditRetentionMode::Delete),
+        "anonymize" => Ok(AuditRetentionMode::Anonymize),
+        other => Err(ConfigError::Invalid {
+            name: "AUDIT_RETENTION_MODE",
+            reason: format!("expected `delete` or `anonymize`, got `{other}`"),
+        }),
+    }
+}
+
+/// Base units per token are `10^decimals`; 28 is the most `Decimal` can scale by.
+fn parse_stake_token_decimals(raw: Option<String>) -> Result<u32, ConfigError> {
+    let Some(raw) = raw else {
+        return Ok(18);
+    };
+    raw.trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|decimals| *decimals <= 28)
+        .ok_or_else(|| ConfigError::Invalid {
+            name: "STAKE_TOKEN_DECIMALS",
+            reason: format!("expected an integer from 0 to 28, got `{raw}`"),
+        })
+}
+
+fn parse_token_length(
+    name: &'static str,
+    raw: Option<String>,
+    default: usize,
+    min: usize,
+) -> Result<usize, ConfigError> {
+    let Some(raw) = raw else {
+        return Ok(default);
+    };
+    raw.trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|len| (min..=MAX_TOKEN_LENGTH).contains(len))
+        .ok_or_else(|| ConfigError::Invalid {
+            name,
+            reason: format!("expected an integer from {min} to {MAX_TOKEN_LENGTH}, got `{raw}`"),
+        })
+}
+
+/// Values sent verbatim as HTTP headers must be non-empty and header-safe.
+fn header_safe_env(name: &'static str, default: &str) -> Result<String, ConfigError> {
+    let value = env::var(name)
+        .map(|v| v.trim().to_string())
+        .unwrap_or_else(|_| default.to_string());
+    if value.is_empty() || http::HeaderValue::from_str(&value).is_err() {
+        return Err(ConfigError::Invalid {
+            name,
+            reason: "must be a non-empty, header-safe string".to_string(),
+        });
+    }
+    Ok(value)
+}
+
+fn parse_rate_limit(name: &str, default: RateLimit) -> RateLimit {
+    RateLimit {
+        max: env::var(format!("RL_{name}_MAX"))
+            .ok()
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(default.max),
+        window_secs: env::var(format!("RL_{name}_WINDOW_SECONDS"))
+            .ok()
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(default.window_secs),
+    }
+}
+
+fn env_flag(name: &str) -> bool {
+    env::var(name)
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
 }
 
 #[cfg(test)]
@@ -78,9 +801,76 @@ mod tests {
         "GITHUB_CLIENT_ID",
         "GITHUB_CLIENT_SECRET",
         "SESSION_COOKIE_NAME",
+        "KEEP_SESSIONS_ON_LOGIN",
         "BLOCKED_UNLINK_WALLETS",
         "BASE_RPC_URL",
         "STAKING_CONTRACT_ADDRESS",
+        "ADMIN_GITHUB_USER_IDS",
+        "TRUST_PROXY_HEADERS",
+        "WALLET_UNLINK_REAUTH_REQUIRED",
+        "GITHUB_OAUTH_SCOPES",
+        "GITHUB_OAUTH_PKCE",
+        "GITHUB_USER_AGENT",
+        "GITHUB_API_VERSION",
+        "BOT_CLAIM_MAX_PER_REQUEST",
+        "GATE_CONFIRM_MAX_ATTEMPTS",
+        "GATE_CONFIRM_LOCKOUT_SECONDS",
+        "AUDIT_RETENTION_MODE",
+        "AUDIT_ANONYMIZE_KEY",
+        "MAINTENANCE_MODE",
+        "QUOTE_STALE_WHILE_REVALIDATE_SECONDS",
+        "ENS_REVERSE_RESOLVER_ADDRESS",
+        "ENS_REVERSE_NAMESPACE",
+        "WALLET_IDENTITY_REQUIRED",
+        "GITHUB_DELIVERY_DEDUPE_WINDOW_SECONDS",
+        "STAKE_TOKEN_DECIMALS",
+        "TIMEOUT_CLOSE_COMMENT",
+        "OUTBOUND_WEBHOOK_URL",
+        "OUTBOUND_WEBHOOK_SECRET",
+        "OUTBOUND_WEBHOOK_EVENTS",
+        "MAX_PENDING_CHALLENGES_PER_REPO",
+        "JOB_JITTER_PERCENT",
+        "COMMENT_MAX_CHARS",
+        "GATE_TOKEN_LENGTH",
+        "SESSION_TOKEN_LENGTH",
+        "BOT_CLAIM_MAX_RESPONSE_BYTES",
+        "REDIS_URL",
+        "HTTP_POOL_MAX_IDLE_PER_HOST",
+        "HTTP_POOL_IDLE_TIMEOUT_SECONDS",
+        "RL_AUTH_START_MAX",
+        "RL_AUTH_START_WINDOW_SECONDS",
+        "RL_AUTH_CALLBACK_IP_MAX",
+        "RL_AUTH_CALLBACK_IP_WINDOW_SECONDS",
+        "RL_AUTH_CALLBACK_MAX",
+        "RL_AUTH_CALLBACK_WINDOW_SECONDS",
+        "RL_WALLET_CHALLENGE_MAX",
+        "RL_WALLET_CHALLENGE_WINDOW_SECONDS",
+        "RL_WALLET_CONFIRM_MAX",
+        "RL_WALLET_CONFIRM_WINDOW_SECONDS",
+        "RL_GATE_MAX",
+        "RL_GATE_WINDOW_SECONDS",
+        "RL_GATE_VERIFIED_MAX",
+        "RL_GATE_VERIFIED_WINDOW_SECONDS",
+        "RL_QUOTE_MAX",
+        "RL_QUOTE_WINDOW_SECONDS",
+        "RL_STAKE_BATCH_MAX",
+        "RL_STAKE_BATCH_WINDOW_SECONDS",
+        "QUOTE_CACHE_TTL_SECS",
+        "QUOTE_MAX_STALENESS_SECS",
+        "QUOTE_STRATEGY",
+        "RATE_LIMIT_BYPASS_IPS",
+        "RATE_LIMIT_BYPASS_KEYS",
+        "TYPED_DATA_DOMAIN_VERSION",
+        "TYPED_DATA_ACCEPTED_VERSIONS",
+        "QUOTE_BREAKER_FAILURE_THRESHOLD",
+        "QUOTE_BREAKER_COOLDOWN_SECS",
+        "STAKE_LOCK_MODE",
+        "MULTICALL3_ADDRESS",
+        "STAKE_STATUS_CACHE_TTL_SECS",
+        "STAKE_POSITION_MODE",
+        "SLOW_QUERY_THRESHOLD_MS",
+        "CHAIN_ID",
+        "STAKE_TOKEN_ADDRESS",
     ];
 
     struct EnvSnapshot {
@@ -136,6 +926,10 @@ mod tests {
             env::set_var("PORT", "not-a-number");
             env::set_var("DB_MAX_CONNECTIONS", "invalid");
             env::set_var("BLOCKED_UNLINK_WALLETS", " 0xAbC , , 0xDEF,");
+            env::set_var("ADMIN_GITHUB_USER_IDS", "1001, nope ,1002");
+            env::set_var("TRUST_PROXY_HEADERS", "TRUE");
+            env::set_var("BOT_CLAIM_MAX_PER_REQUEST", "0");
+            env::set_var("GATE_CONFIRM_MAX_ATTEMPTS", "-1");
         }
 
         let config = Config::from_env().expect("config should parse");
@@ -145,10 +939,141 @@ mod tests {
         assert_eq!(config.app_base_url, "https://sitg.io");
         assert_eq!(config.api_base_url, "http://localhost:8080");
         assert_eq!(config.session_cookie_name, "sitg_session");
+        assert!(!config.keep_sessions_on_login);
         assert_eq!(
             config.blocked_unlink_wallets,
             vec!["0xabc".to_string(), "0xdef".to_string()]
         );
+        assert_eq!(config.admin_github_user_ids, vec![1001, 1002]);
+        assert!(config.trust_proxy_headers);
+        assert!(!config.wallet_unlink_reauth_required);
+        assert_eq!(config.github_oauth_scopes, vec!["read:user", "public_repo"]);
+        assert!(!config.github_oauth_pkce);
+        assert_eq!(config.github_user_agent, "sitg-backend");
+        assert_eq!(config.github_api_version, "2022-11-28");
+        assert_eq!(config.bot_claim_max_per_request, 100);
+        assert_eq!(config.gate_confirm_max_attempts, 5);
+        assert_eq!(config.gate_confirm_lockout_seconds, 900);
+        assert_eq!(config.audit_retention_mode, AuditRetentionMode::Delete);
+        assert_eq!(config.audit_anonymize_key, None);
+        assert!(!config.maintenance_mode);
+        assert_eq!(config.quote_stale_while_revalidate_seconds, 0);
+        assert_eq!(config.ens_reverse_resolver_address, None);
+        assert_eq!(config.ens_reverse_namespace, "addr.reverse");
+        assert!(!config.wallet_identity_required);
+        assert_eq!(config.github_delivery_dedupe_window_seconds, 0);
+        assert_eq!(config.stake_token_decimals, 18);
+        assert_eq!(config.timeout_close_comment, None);
+        assert_eq!(config.outbound_webhook_url, None);
+        assert_eq!(
+            config.outbound_webhook_events,
+            vec!["WALLET_LINKED", "WALLET_UNLINKED"]
+        );
+        assert_eq!(config.max_pending_challenges_per_repo, 200);
+        assert_eq!(config.job_jitter_percent, 10);
+        assert_eq!(config.comment_max_chars, 65_000);
+        assert_eq!(config.gate_token_length, 24);
+        assert_eq!(config.session_token_length, 64);
+        assert_eq!(config.bot_claim_max_response_bytes, 1_048_576);
+        assert_eq!(config.redis_url, None);
+        assert_eq!(config.http_pool_max_idle_per_host, None);
+        assert_eq!(config.http_pool_idle_timeout_seconds, 90);
+        assert_eq!(config.rate_limits, RateLimitConfig::default());
+        assert_eq!(config.quote_cache_ttl_secs, 300);
+        assert_eq!(config.quote_max_staleness_secs, 3600);
+        assert_eq!(config.quote_strategy, QuoteStrategy::FirstSuccess);
+        assert!(config.rate_limit_bypass_ips.is_empty());
+        assert!(config.rate_limit_bypass_keys.is_empty());
+        assert_eq!(config.typed_data_domain_version, "1");
+        assert_eq!(config.typed_data_accepted_versions, vec!["1".to_string()]);
+        assert_eq!(config.quote_breaker_failure_threshold, 3);
+        assert_eq!(config.quote_breaker_cooldown_secs, 30);
+        assert_eq!(config.stake_lock_mode, StakeLockMode::Required);
+        assert_eq!(config.multicall3_address, None);
+        assert_eq!(config.stake_status_cache_ttl_secs, 0);
+        assert_eq!(config.stake_position_mode, StakePositionMode::Single);
+        assert_eq!(config.slow_query_threshold_ms, 1000);
+        assert_eq!(config.chain_id, 8453);
+        assert_eq!(config.stake_token_address, None);
+    }
+
+    #[test]
+    fn validates_token_lengths() {
+        assert_eq!(
+            parse_token_length("GATE_TOKEN_LENGTH", Some("40".to_string()), 24, 16).ok(),
+            Some(40)
+        );
+        assert_eq!(
+            parse_token_length("GATE_TOKEN_LENGTH", Some("16".to_string()), 24, 16).ok(),
+            Some(16)
+        );
+        for raw in ["15", "257", "long"] {
+            assert!(matches!(
+                parse_token_length("GATE_TOKEN_LENGTH", Some(raw.to_string()), 24, 16),
+                Err(ConfigError::Invalid {
+                    name: "GATE_TOKEN_LENGTH",
+                    ..
+                })
+            ));
+        }
+
+        let _lock = env_lock().lock().expect("env lock");
+        let _snapshot = EnvSnapshot::capture();
+        EnvSnapshot::clear_tracked();
+        unsafe {
+            env::set_var("DATABASE_URL", "postgres://localhost/sitg");
+            env::set_var("SESSION_TOKEN_LENGTH", "31");
+        }
+        assert!(matches!(
+            Config::from_env(),
+            Err(ConfigError::Invalid {
+                name: "SESSION_TOKEN_LENGTH",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn validates_stake_token_decimals() {
+        assert_eq!(
+            parse_stake_token_decimals(Some("6".to_string())).ok(),
+            Some(6)
+        );
+        for raw in ["29", "-1", "six"] {
+            assert!(matches!(
+                parse_stake_token_decimals(Some(raw.to_string())),
+                Err(ConfigError::Invalid {
+                    name: "STAKE_TOKEN_DECIMALS",
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn validates_redis_url() {
+        let _lock = env_lock().lock().expect("env lock");
+        let _snapshot = EnvSnapshot::capture();
+        EnvSnapshot::clear_tracked();
+        unsafe {
+            env::set_var("DATABASE_URL", "postgres://localhost/sitg");
+            env::set_var("REDIS_URL", "redis://cache.internal:6379/0");
+        }
+        assert_eq!(
+            Config::from_env().expect("config").redis_url.as_deref(),
+            Some("redis://cache.internal:6379/0")
+        );
+
+        unsafe {
+            env::set_var("REDIS_URL", "cache.internal:6379");
+        }
+        assert!(matches!(
+            Config::from_env(),
+            Err(ConfigError::Invalid {
+                name: "REDIS_URL",
+                ..
+            })
+        ));
     }
 
     #[test]
@@ -158,6 +1083,279 @@ mod tests {
         EnvSnapshot::clear_tracked();
 
         let err = Config::from_env().expect_err("DATABASE_URL should be required");
-        assert!(matches!(err, env::VarError::NotPresent));
+        assert!(matches!(err, ConfigError::Env(env::VarError::NotPresent)));
+    }
+
+    #[test]
+    fn parses_and_validates_outbound_webhook_settings() {
+        let _lock = env_lock().lock().expect("env lock");
+        let _snapshot = EnvSnapshot::capture();
+        EnvSnapshot::clear_tracked();
+
+        unsafe {
+            env::set_var("DATABASE_URL", "postgres://localhost/sitg");
+            env::set_var("OUTBOUND_WEBHOOK_URL", "https://hooks.example.com/sitg");
+            env::set_var(
+                "OUTBOUND_WEBHOOK_EVENTS",
+                "wallet_linked, WALLET_UNLINKED,WALLET_LINKED",
+            );
+        }
+        let err = Config::from_env().expect_err("secret required with url");
+        assert!(matches!(
+            err,
+            ConfigError::Invalid {
+                name: "OUTBOUND_WEBHOOK_SECRET",
+                ..
+            }
+        ));
+
+        unsafe {
+            env::set_var("OUTBOUND_WEBHOOK_SECRET", "hook-secret");
+        }
+        let config = Config::from_env().expect("config should parse");
+        assert_eq!(
+            config.outbound_webhook_events,
+            vec!["WALLET_LINKED", "WALLET_UNLINKED"]
+        );
+
+        unsafe {
+            env::set_var("OUTBOUND_WEBHOOK_EVENTS", "WALLET_LINKED,PR_CLOSED");
+        }
+        let err = Config::from_env().expect_err("unknown event should fail");
+        assert!(matches!(
+            err,
+            ConfigError::Invalid {
+                name: "OUTBOUND_WEBHOOK_EVENTS",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn parses_rate_limit_overrides() {
+        let _lock = env_lock().lock().expect("env lock");
+        let _snapshot = EnvSnapshot::capture();
+        EnvSnapshot::clear_tracked();
+
+        unsafe {
+            env::set_var("DATABASE_URL", "postgres://localhost/sitg");
+            env::set_var("RL_AUTH_START_MAX", " 500 ");
+            env::set_var("RL_AUTH_START_WINDOW_SECONDS", "30");
+            env::set_var("RL_WALLET_CONFIRM_MAX", "0");
+            env::set_var("RL_GATE_MAX", "many");
+            env::set_var("RL_GATE_VERIFIED_WINDOW_SECONDS", "-5");
+            env::set_var("RL_AUTH_CALLBACK_IP_MAX", "5");
+            env::set_var("RATE_LIMIT_BYPASS_IPS", "203.0.113.7, ::1");
+            env::set_var("RATE_LIMIT_BYPASS_KEYS", " monitor-key ,");
+        }
+        let config = Config::from_env().expect("config should parse");
+        let defaults = RateLimitConfig::default();
+        assert_eq!(
+            config.rate_limits.auth_start,
+            RateLimit {
+                max: 500,
+                window_secs: 30
+            }
+        );
+        assert_eq!(
+            config.rate_limits.auth_callback_ip,
+            RateLimit {
+                max: 5,
+                window_secs: 60
+            }
+        );
+        assert_eq!(config.rate_limits.auth_callback, defaults.auth_callback);
+        assert_eq!(
+            config.rate_limit_bypass_ips,
+            vec![
+                "203.0.113.7".parse::<IpAddr>().expect("ip"),
+                "::1".parse::<IpAddr>().expect("ip")
+            ]
+        );
+        assert_eq!(
+            config.rate_limit_bypass_keys,
+            vec!["monitor-key".to_string()]
+        );
+
+        unsafe {
+            env::set_var("RATE_LIMIT_BYPASS_IPS", "203.0.113.0/24");
+        }
+        assert!(Config::from_env().is_err());
+        assert_eq!(config.rate_limits.wallet_confirm, defaults.wallet_confirm);
+        assert_eq!(config.rate_limits.gate, defaults.gate);
+        assert_eq!(config.rate_limits.gate_verified, defaults.gate_verified);
+        assert_eq!(defaults.auth_start.max, 100);
+        assert_eq!(defaults.wallet_confirm.max, 30);
+    }
+
+    #[test]
+    fn parses_and_validates_github_oauth_scopes() {
+        let _lock = env_lock().lock().expect("env lock");
+        let _snapshot = EnvSnapshot::capture();
+        EnvSnapshot::clear_tracked();
+
+        unsafe {
+            env::set_var("DATABASE_URL", "postgres://localhost/sitg");
+            env::set_var("GITHUB_OAUTH_SCOPES", "read:user, repo repo");
+        }
+        let config = Config::from_env().expect("config should parse");
+        assert_eq!(config.github_oauth_scopes, vec!["read:user", "repo"]);
+
+        unsafe {
+            env::set_var("GITHUB_OAUTH_SCOPES", "read:user admin:org");
+        }
+        let err = Config::from_env().expect_err("unknown scope should fail");
+        assert!(matches!(
+            err,
+            ConfigError::Invalid {
+                name: "GITHUB_OAUTH_SCOPES",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn anonymize_mode_requires_a_key() {
+        let _lock = env_lock().lock().expect("env lock");
+        let _snapshot = EnvSnapshot::capture();
+        EnvSnapshot::clear_tracked();
+
+        unsafe {
+            env::set_var("DATABASE_URL", "postgres://localhost/sitg");
+            env::set_var("AUDIT_RETENTION_MODE", "anonymize");
+        }
+        let err = Config::from_env().expect_err("key required in anonymize mode");
+        assert!(matches!(
+            err,
+            ConfigError::Invalid {
+                name: "AUDIT_ANONYMIZE_KEY",
+                ..
+            }
+        ));
+
+        unsafe {
+            env::set_var("AUDIT_ANONYMIZE_KEY", "audit-key");
+        }
+        let config = Config::from_env().expect("config should parse");
+        assert_eq!(config.audit_anonymize_key.as_deref(), Some("audit-key"));
+    }
+
+    #[test]
+    fn parses_audit_retention_mode() {
+        assert_eq!(
+            parse_audit_retention_mode(" Anonymize ").expect("mode"),
+            AuditRetentionMode::Anonymize
+        );
+        assert!(matches!(
+            parse_audit_retention_mode("archive"),
+            Err(ConfigError::Invalid {
+                name: "AUDIT_RETENTION_MODE",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn accepts_the_current_typed_data_version_before_prior_ones() {
+        assert_eq!(
+            parse_typed_data_accepted_versions("2", " 1, 2 ,,1"),
+            vec!["2".to_string(), "1".to_string()]
+        );
+        assert_eq!(
+            parse_typed_data_accepted_versions("1", ""),
+            vec!["1".to_string()]
+        );
+    }
+
+    #[test]
+    fn parses_quote_strategy() {
+        assert_eq!(
+            parse_quote_strategy(" Median ").expect("strategy"),
+            QuoteStrategy::Median
+        );
+        assert_eq!(
+            parse_quote_strategy("first_success").expect("strategy"),
+            QuoteStrategy::FirstSuccess
+        );
+        assert_eq!(
+            parse_quote_strategy("FASTEST").expect("strategy"),
+            QuoteStrategy::Fastest
+        );
+        assert!(matches!(
+            parse_quote_strategy("average"),
+            Err(ConfigError::Invalid {
+                name: "QUOTE_STRATEGY",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parses_stake_lock_mode() {
+        assert_eq!(
+            parse_stake_lock_mode(" Disabled ").expect("mode"),
+            StakeLockMode::Disabled
+        );
+        assert_eq!(
+            parse_stake_lock_mode("required").expect("mode"),
+            StakeLockMode::Required
+        );
+        assert!(matches!(
+            parse_stake_lock_mode("off"),
+            Err(ConfigError::Invalid {
+                name: "STAKE_LOCK_MODE",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parses_stake_position_mode() {
+        assert_eq!(
+            parse_stake_position_mode("Positions").expect("mode"),
+            StakePositionMode::Positions
+        );
+        assert_eq!(
+            parse_stake_position_mode(" single ").expect("mode"),
+            StakePositionMode::Single
+        );
+        assert!(matches!(
+            parse_stake_position_mode("many"),
+            Err(ConfigError::Invalid {
+                name: "STAKE_POSITION_MODE",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn parses_base_rpc_urls_in_failover_order() {
+        assert_eq!(
+            parse_base_rpc_urls(" https://primary.example , ,https://backup.example"),
+            vec![
+                "https://primary.example".to_string(),
+                "https://backup.example".to_string()
+            ]
+        );
+        assert!(parse_base_rpc_urls("").is_empty());
+    }
+
+    #[test]
+    fn parses_chain_id_with_base_mainnet_default() {
+        assert_eq!(parse_chain_id(None).expect("default"), 8453);
+        assert_eq!(
+            parse_chain_id(Some(" ")).expect("default"),
+            DEFAULT_CHAIN_ID
+        );
+        assert_eq!(parse_chain_id(Some("84532")).expect("sepolia"), 84532);
+        for invalid in ["base", "0", "-1"] {
+            assert!(matches!(
+                parse_chain_id(Some(invalid)),
+                Err(ConfigError::Invalid {
+                    name: "CHAIN_ID",
+                    ..
+                })
+            ));
+        }
     }
 }
@@ -1,5 +1,14 @@
-use axum::{Json, http::StatusCode, response::IntoResponse};
+use axum::{
+    Json,
+    http::{HeaderValue, StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
 use serde::Serialize;
+use serde_json::{Value, json};
+
+/// Clients are told to retry a price-dependent request after this many seconds.
+const PRICE_RETRY_AFTER_SECONDS: u32 = 30;
 
 #[derive(thiserror::Error, Debug)]
 pub enum ApiError {
@@ -9,12 +18,21 @@ pub enum ApiError {
     Forbidden,
     #[error("not found")]
     NotFound,
+    /// Malformed input: missing fields, unparseable ids, numbers or hex.
     #[error("validation error: {0}")]
     Validation(String),
+    /// Well-formed input that breaks a business rule, e.g. a non-positive amount.
+    #[error("validation error: {0}")]
+    Unprocessable(String),
+    /// Carries when the last usable quote was fetched, if there ever was one.
     #[error("price unavailable")]
-    PriceUnavailable,
+    PriceUnavailable(Option<DateTime<Utc>>),
     #[error("conflict: {0}")]
     Conflict(&'static str),
+    #[error("rate limited; retry after {retry_after_secs}s")]
+    RateLimited { retry_after_secs: u64 },
+    #[error("service is in maintenance mode; writes are temporarily disabled")]
+    Maintenance,
     #[error(transparent)]
     Db(#[from] sqlx::Error),
     #[error(transparent)]
@@ -30,6 +48,8 @@ struct ErrorBody {
 struct ErrorPayload {
     code: String,
     message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<Value>,
 }
 
 impl ApiError {
@@ -37,15 +57,30 @@ impl ApiError {
         Self::Validation(msg.into())
     }
 
+    pub fn unprocessable(msg: impl Into<String>) -> Self {
+        Self::Unprocessable(msg.into())
+    }
+
     fn as_code(&self) -> &'static str {
         match self {
             ApiError::Unauthenticated => "UNAUTHENTICATED",
             ApiError::Forbidden => "FORBIDDEN",
             ApiError::NotFound => "NOT_FOUND",
-            ApiError::Validation(_) => "VALIDATION_ERROR",
-            ApiError::PriceUnavailable => "PRICE_UNAVAILABLE",
-            ApiError::Conflict("WALLET_HAS_STAKE") => "WALLET_HAS_STAKE",
+            // Both share a code so clients keyed on it keep working; the status tells them apart.
+            ApiError::Validation(_) | ApiError::Unprocessable(_) => "VALIDATION_ERROR",
+            ApiError::PriceUnavailable(_) => "PRICE_UNAVAILABLE",
+            // Wallet link and unlink failures each need different guidance in the UI.
+            ApiError::Conflict(
+                code @ ("WALLET_HAS_STAKE"
+                | "WALLET_LINK_CHALLENGE_INVALID"
+                | "SIGNER_MISMATCH"
+                | "WALLET_ALREADY_LINKED"
+                | "CHAIN_ID_MISMATCH"
+                | "WALLET_IDENTITY_REQUIRED"),
+            ) => code,
             ApiError::Conflict(_) => "CONFLICT",
+            ApiError::RateLimited { .. } => "RATE_LIMITED",
+            ApiError::Maintenance => "MAINTENANCE",
             ApiError::Db(_) | ApiError::Internal(_) => "INTERNAL_ERROR",
         }
     }
@@ -56,24 +91,56 @@ impl ApiError {
             ApiError::Forbidden => StatusCode::FORBIDDEN,
             ApiError::NotFound => StatusCode::NOT_FOUND,
             ApiError::Validation(_) => StatusCode::BAD_REQUEST,
-            ApiError::PriceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Unprocessable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::PriceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
             ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Maintenance => StatusCode::SERVICE_UNAVAILABLE,
             ApiError::Db(_) | ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    fn details(&self) -> Option<Value> {
+        match self {
+            ApiError::PriceUnavailable(last_quote_at) => Some(json!({
+                "last_quote_at": last_quote_at,
+                "retry_after_seconds": PRICE_RETRY_AFTER_SECONDS,
+            })),
+            ApiError::RateLimited { retry_after_secs } => {
+                Some(json!({ "retry_after": retry_after_secs }))
+            }
+            _ => None,
+        }
+    }
+
+    /// Seconds for the `Retry-After` header, for errors the client should simply retry later.
+    fn retry_after_secs(&self) -> Option<u64> {
+        match self {
+            ApiError::PriceUnavailable(_) => Some(PRICE_RETRY_AFTER_SECONDS.into()),
+            ApiError::RateLimited { retry_after_secs } => Some(*retry_after_secs),
+            _ => None,
+        }
+    }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> axum::response::Response {
         let status = self.as_status();
-        let message = self.to_string();
+        let retry_after = self.retry_after_secs();
         let body = ErrorBody {
             error: ErrorPayload {
                 code: self.as_code().to_string(),
-                message,
+                message: self.to_string(),
+                details: self.details(),
             },
         };
-        (status, Json(body)).into_response()
+        let mut response = (status, Json(body)).into_response();
+        if let Some(seconds) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response
     }
 }
 
@@ -82,8 +149,27 @@ pub type ApiResult<T> = Result<T, ApiError>;
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum::{body::to_bytes, response::IntoResponse};
-    use serde_json::Value;
+    use axum::body::to_bytes;
+
+    #[tokio::test]
+    async fn price_unavailable_carries_retry_guidance() {
+        let fetched_at = DateTime::parse_from_rfc3339("2026-02-13T00:00:00Z")
+            .expect("timestamp")
+            .with_timezone(&Utc);
+        let response = ApiError::PriceUnavailable(Some(fetched_at)).into_response();
+        assert_eq!(response.headers()[header::RETRY_AFTER], "30");
+
+        let (status, payload) = error_payload(ApiError::PriceUnavailable(Some(fetched_at))).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(payload["error"]["code"], "PRICE_UNAVAILABLE");
+        assert_eq!(
+            payload["error"]["details"],
+            json!({"last_quote_at": "2026-02-13T00:00:00Z", "retry_after_seconds": 30})
+        );
+
+        let (_, payload) = error_payload(ApiError::PriceUnavailable(None)).await;
+        assert_eq!(payload["error"]["details"]["last_quote_at"], Value::Null);
+    }
 
     async fn error_payload(err: ApiError) -> (StatusCode, Value) {
         let response = err.into_response();
@@ -102,6 +188,21 @@ mod tests {
         assert_eq!(payload["error"]["code"], "WALLET_HAS_STAKE");
     }
 
+    #[tokio::test]
+    async fn maps_each_wallet_link_conflict_to_its_own_code() {
+        for code in [
+            "WALLET_LINK_CHALLENGE_INVALID",
+            "SIGNER_MISMATCH",
+            "WALLET_ALREADY_LINKED",
+            "CHAIN_ID_MISMATCH",
+            "WALLET_IDENTITY_REQUIRED",
+        ] {
+            let (status, payload) = error_payload(ApiError::Conflict(code)).await;
+            assert_eq!(status, StatusCode::CONFLICT);
+            assert_eq!(payload["error"]["code"], code);
+        }
+    }
+
     #[tokio::test]
     async fn maps_generic_conflict_to_conflict_code() {
         let (status, payload) = error_payload(ApiError::Conflict("RATE_LIMITED")).await;
@@ -109,10 +210,51 @@ mod tests {
         assert_eq!(payload["error"]["code"], "CONFLICT");
     }
 
+    #[tokio::test]
+    async fn rate_limited_is_429_with_retry_after() {
+        let response = ApiError::RateLimited {
+            retry_after_secs: 42,
+        }
+        .into_response();
+        assert_eq!(response.headers()[header::RETRY_AFTER], "42");
+
+        let (status, payload) = error_payload(ApiError::RateLimited {
+            retry_after_secs: 42,
+        })
+        .await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(payload["error"]["code"], "RATE_LIMITED");
+        assert_eq!(payload["error"]["details"], json!({"retry_after": 42}));
+    }
+
+    #[tokio::test]
+    async fn splits_malformed_and_rule_breaking_input() {
+        let (status, payload) =
+            error_payload(ApiError::validation("nonce must be a valid UUID")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(payload["error"]["code"], "VALIDATION_ERROR");
+
+        let (status, payload) =
+            error_payload(ApiError::unprocessable("input_value must be > 0")).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(payload["error"]["code"], "VALIDATION_ERROR");
+        assert_eq!(
+            payload["error"]["message"],
+            "validation error: input_value must be > 0"
+        );
+    }
+
     #[tokio::test]
     async fn maps_internal_error_to_internal_status_and_code() {
         let (status, payload) = error_payload(ApiError::Internal(anyhow::anyhow!("boom"))).await;
         assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
         assert_eq!(payload["error"]["code"], "INTERNAL_ERROR");
     }
+
+    #[tokio::test]
+    async fn maps_maintenance_to_service_unavailable() {
+        let (status, payload) = error_payload(ApiError::Maintenance).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(payload["error"]["code"], "MAINTENANCE");
+    }
 }
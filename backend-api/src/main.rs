@@ -5,7 +5,7 @@ mod models;
 mod routes;
 mod services;
 
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use app::AppState;
 use config::Config;
@@ -25,7 +25,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = Config::from_env()?;
     let pool = PgPoolOptions::new()
         .max_connections(config.db_max_connections)
-        .connect(&config.database_url)
+        .connect_with(app::pg_connect_options(&config)?)
         .await?;
     sqlx::migrate!("./migrations").run(&pool).await?;
 
@@ -36,7 +36,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let listener = tokio::net::TcpListener::bind((config.host.as_str(), config.port)).await?;
     tracing::info!(host = %config.host, port = config.port, "backend-api listening");
 
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
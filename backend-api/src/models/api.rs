@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -23,6 +25,16 @@ pub struct MeResponse {
     pub github_login: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    pub id: Uuid,
+    pub current: bool,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct RepoOptionResponse {
     pub id: i64,
@@ -42,7 +54,14 @@ pub struct RepoGithubAppStatusResponse {
 pub struct RepoConfigPutRequest {
     pub input_mode: String,
     pub input_value: String,
+    /// Fiat currency of a `FIAT` input (`USD`, `EUR` or `GBP`); defaults to `USD`.
+    #[serde(default)]
+    pub currency: Option<String>,
     pub draft_prs_gated: bool,
+    /// Users whose staked wallets may satisfy any author's threshold; omitted keeps the current
+    /// pool, `[]` turns pooling off.
+    #[serde(default)]
+    pub pooled_stake_github_user_ids: Option<Vec<i64>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -50,23 +69,59 @@ pub struct RepoConfigResponse {
     pub github_repo_id: i64,
     pub threshold: ThresholdResponse,
     pub draft_prs_gated: bool,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub pooled_stake_github_user_ids: Vec<i64>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ThresholdResponse {
     pub wei: String,
     pub eth: String,
-    pub usd_estimate: String,
+    /// `None` for `TOKEN` mode, which is never priced.
+    pub usd_estimate: Option<String>,
     pub input_mode: String,
     pub input_value: String,
-    pub spot_price_usd: String,
-    pub spot_source: String,
-    pub spot_at: DateTime<Utc>,
+    /// Currency `spot_price_usd` and `usd_estimate` are denominated in; `None` for `TOKEN` and
+    /// whenever a non-ETH stake token is configured.
+    pub currency: Option<String>,
+    pub spot_price_usd: Option<String>,
+    pub spot_source: Option<String>,
+    pub spot_at: Option<DateTime<Utc>>,
     pub spot_from_cache: bool,
     pub spot_quote_id: Option<Uuid>,
     pub message: String,
 }
 
+/// What PR events for a repo actually run with: its own settings where it has them, global
+/// settings and built-in defaults everywhere else.
+#[derive(Debug, Serialize)]
+pub struct EffectiveRepoConfigResponse {
+    pub github_repo_id: i64,
+    /// `ENFORCED`, `ARCHIVED` or `NOT_CONFIGURED`; PR events are ignored unless `ENFORCED`.
+    pub enforcement: String,
+    /// `None` until the repo has a config.
+    pub threshold_wei: Option<String>,
+    pub input_mode: Option<String>,
+    pub draft_prs_gated: EffectiveSetting<bool>,
+    pub pooled_stake_github_user_ids: EffectiveSetting<Vec<i64>>,
+    pub chain_id: EffectiveSetting<u64>,
+    pub staking_contract_address: EffectiveSetting<Option<String>>,
+    pub stake_token_decimals: EffectiveSetting<u32>,
+    pub deadline_minutes: EffectiveSetting<i64>,
+    pub max_pending_challenges: EffectiveSetting<i64>,
+    pub gate_confirm_max_attempts: EffectiveSetting<i32>,
+    pub gate_confirm_lockout_seconds: EffectiveSetting<i64>,
+    pub timeout_close_comment: EffectiveSetting<String>,
+}
+
+/// A resolved value and where it came from: `repo` (this repo's config), `global` (a server-wide
+/// environment setting or its default) or `default` (built in, used when the repo sets nothing).
+#[derive(Debug, Serialize, PartialEq)]
+pub struct EffectiveSetting<T> {
+    pub value: T,
+    pub source: &'static str,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ResolveLoginsRequest {
     pub logins: Vec<String>,
@@ -78,7 +133,7 @@ pub struct ResolveLoginsResponse {
     pub unresolved: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResolvedLogin {
     pub github_user_id: i64,
     pub github_login: String,
@@ -87,6 +142,38 @@ pub struct ResolvedLogin {
 #[derive(Debug, Deserialize)]
 pub struct WhitelistPutRequest {
     pub entries: Vec<ResolvedLogin>,
+    /// Re-check every id against GitHub before inserting, for entries resolved a while ago.
+    #[serde(default)]
+    pub revalidate: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SkippedWhitelistEntry {
+    pub github_user_id: i64,
+    pub github_login: String,
+    pub reason: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WhitelistPutResponse {
+    pub inserted: Vec<ResolvedLogin>,
+    pub skipped: Vec<SkippedWhitelistEntry>,
+}
+
+/// Served by `GET /api/v1/config/public`; must never carry secrets.
+#[derive(Debug, Serialize)]
+pub struct PublicConfigResponse {
+    pub chain_id: u64,
+    pub staking_contract_address: Option<String>,
+    pub app_base_url: String,
+    pub gate_path_prefix: String,
+}
+
+/// Token-less gate status for CI and branch protection checks.
+#[derive(Debug, Serialize)]
+pub struct GateVerifiedResponse {
+    pub verified: bool,
+    pub status: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -96,18 +183,43 @@ pub struct GateResponse {
     pub github_repo_id: i64,
     pub github_repo_full_name: String,
     pub github_pr_number: i32,
-    pub github_pr_author_id: i64,
-    pub github_pr_author_login: String,
+    pub github_pr_author_id: Option<i64>,
+    pub github_pr_author_login: Option<String>,
     pub head_sha: String,
     pub deadline_at: DateTime<Utc>,
     pub threshold_wei_snapshot: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct GateStakePreviewResponse {
+    pub wallet_address: Option<String>,
+    pub staked_balance_wei: Option<String>,
+    pub threshold_wei_snapshot: String,
+    pub unlock_time: Option<DateTime<Utc>>,
+    pub meets_threshold: bool,
+    pub outcome: String,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ConfirmTypedDataResponse {
     pub domain: TypedDataDomain,
     pub primary_type: String,
     pub message: TypedDataMessage,
+    pub nonce_display: NonceDisplay,
+}
+
+/// The EIP-712 digest the server recovers confirmations against, with the typed data it hashed.
+#[derive(Debug, Serialize)]
+pub struct ConfirmDigestResponse {
+    pub digest: String,
+    pub typed_data: ConfirmTypedDataResponse,
+}
+
+/// Both renderings of the signed `nonce`; wallets differ in how they show a uint256.
+#[derive(Debug, Serialize)]
+pub struct NonceDisplay {
+    pub decimal: String,
+    pub hex: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -140,6 +252,12 @@ pub struct TypedDataMessage {
 #[derive(Debug, Deserialize)]
 pub struct ConfirmRequest {
     pub signature: String,
+    /// `expiresAt` from the typed data the client signed, when it reports it.
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    /// `chainId` from the typed-data domain the client signed, when it reports it.
+    #[serde(default)]
+    pub chain_id: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -154,17 +272,54 @@ pub struct WalletLinkChallengeResponse {
     pub message: String,
 }
 
+/// EIP-712 form of the active wallet-link challenge, for wallets that cannot `personal_sign`.
+#[derive(Debug, Serialize)]
+pub struct WalletLinkTypedDataResponse {
+    pub domain: TypedDataDomain,
+    pub types: BTreeMap<String, Vec<TypedDataField>>,
+    pub primary_type: String,
+    pub message: WalletLinkTypedDataMessage,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TypedDataField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WalletLinkTypedDataMessage {
+    #[serde(rename = "githubUserId")]
+    pub github_user_id: i64,
+    pub nonce: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: i64,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct WalletLinkConfirmRequest {
     pub nonce: String,
     pub wallet_address: String,
     pub signature: String,
+    /// Chain the wallet was on when it signed, when the client reports it.
+    #[serde(default)]
+    pub chain_id: Option<u64>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct WalletLinkConfirmResponse {
     pub wallet_address: String,
     pub linked: bool,
+    /// Primary name resolved for the wallet when identity lookups are configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ens_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WalletUnlinkRequest {
+    pub nonce: String,
+    pub signature: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -174,19 +329,188 @@ pub struct WalletLinkStatusResponse {
     pub linked_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct WalletDelegationChallengeRequest {
+    pub cold_wallet_address: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WalletDelegationConfirmRequest {
+    pub cold_wallet_address: String,
+    pub nonce: String,
+    pub signature: String,
+}
+
+/// Active delegation: the linked (hot) wallet signs gate confirmations, stake is read from the
+/// cold wallet.
+#[derive(Debug, Serialize)]
+pub struct WalletDelegationResponse {
+    pub hot_wallet_address: String,
+    pub cold_wallet_address: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WalletRecoveryChallengeRequest {
+    pub recovery_wallet_address: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WalletRecoveryConfirmRequest {
+    pub recovery_wallet_address: String,
+    pub nonce: String,
+    pub signature: String,
+}
+
+/// Active recovery wallet: it may sign gate confirmations in place of the linked wallet, whose
+/// stake still backs them.
+#[derive(Debug, Serialize)]
+pub struct WalletRecoveryResponse {
+    pub wallet_address: String,
+    pub recovery_wallet_address: String,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct StakeStatusQuery {
     pub wallet: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct AdminListQuery {
+    pub limit: Option<i64>,
+    pub after: Option<String>,
+}
+
+/// Re-pends actions whose claim is older than `stuck_after_minutes` (default 15), plus `FAILED`
+/// ones when `include_failed` is set.
+#[derive(Debug, Deserialize)]
+pub struct BotActionRequeueRequest {
+    pub dry_run: bool,
+    pub stuck_after_minutes: Option<i64>,
+    #[serde(default)]
+    pub include_failed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BotActionRequeueResponse {
+    pub dry_run: bool,
+    pub pending: i64,
+    pub claimed: i64,
+    pub failed: i64,
+    /// Actions that match the request; all of them were re-pended unless `dry_run`.
+    pub requeue_candidates: i64,
+    pub requeued: i64,
+}
+
+/// Owner challenge list: `after` is the last challenge id of the previous page; author filters
+/// combine with AND.
+#[derive(Debug, Default, Deserialize)]
+pub struct ChallengeListQuery {
+    pub limit: Option<i64>,
+    pub after: Option<String>,
+    pub author_login: Option<String>,
+    pub author_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChallengeSummaryResponse {
+    pub id: Uuid,
+    pub github_pr_number: i32,
+    pub github_pr_author_id: i64,
+    pub github_pr_author_login: String,
+    pub head_sha: String,
+    pub status: String,
+    pub deadline_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Stored confirmation of a verified challenge, for on-chain submission or audit.
+#[derive(Debug, Serialize)]
+pub struct ChallengeProofResponse {
+    pub challenge_id: Uuid,
+    pub github_repo_id: i64,
+    pub github_pr_number: i32,
+    pub github_pr_author_id: i64,
+    pub head_sha: String,
+    pub signature: String,
+    pub signer_address: String,
+    pub typed_data: serde_json::Value,
+    pub verified_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChallengeListResponse {
+    pub challenges: Vec<ChallengeSummaryResponse>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServiceBotKeyResponse {
+    pub key_id: String,
+    pub active: bool,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ServiceBotKeyListResponse {
+    pub keys: Vec<ServiceBotKeyResponse>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QuoteResponse {
+    pub price: String,
+    pub source: String,
+    pub fetched_at: DateTime<Utc>,
+    pub from_cache: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpotQuoteResponse {
+    pub id: Uuid,
+    pub source: String,
+    pub price_usd: String,
+    pub fetched_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpotQuoteListResponse {
+    pub quotes: Vec<SpotQuoteResponse>,
+    pub next_cursor: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct StakeStatusResponse {
     pub staked_balance_wei: String,
-    pub unlock_time: DateTime<Utc>,
+    /// `null` when `STAKE_LOCK_MODE=disabled`; stake then counts as active whenever non-zero.
+    pub unlock_time: Option<DateTime<Utc>>,
     pub lock_active: bool,
 }
 
 #[derive(Debug, Deserialize)]
+pub struct StakeStatusBatchRequest {
+    pub wallets: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StakeStatusBatchEntry {
+    pub wallet: String,
+    pub staked_balance_wei: String,
+    /// `null` when `STAKE_LOCK_MODE=disabled`; stake then counts as active whenever non-zero.
+    pub unlock_time: Option<DateTime<Utc>>,
+    pub lock_active: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StakeStatusBatchResponse {
+    pub statuses: Vec<StakeStatusBatchEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct InternalPrEventRequest {
     pub delivery_id: String,
     pub event_time: DateTime<Utc>,
@@ -196,13 +520,13 @@ pub struct InternalPrEventRequest {
     pub pull_request: InternalPullRequest,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct InternalRepository {
     pub id: i64,
     pub full_name: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct InternalPullRequest {
     pub number: i32,
@@ -213,7 +537,7 @@ pub struct InternalPullRequest {
     pub is_draft: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct InternalPrUser {
     pub id: i64,
     pub login: String,
@@ -224,6 +548,10 @@ pub struct InternalPrEventResponse {
     pub ingest_status: String,
     pub challenge_id: Option<Uuid>,
     pub enqueued_actions: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignored_reason: Option<String>,
+    /// The PR is a draft and was gated because the repo has `draft_prs_gated` on.
+    pub draft_gated: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -255,6 +583,17 @@ pub struct InternalInstallationSyncResponse {
     pub updated_repositories: i32,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct InternalAuthPingRequest {
+    pub nonce: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InternalAuthPingResponse {
+    pub key_id: String,
+    pub ok: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BotActionClaimRequest {
     pub worker_id: String,
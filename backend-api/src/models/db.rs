@@ -11,11 +11,40 @@ pub struct RepoConfigRow {
     pub threshold_wei: Decimal,
     pub input_mode: String,
     pub input_value: Decimal,
-    pub spot_price_usd: Decimal,
-    pub spot_source: String,
-    pub spot_at: DateTime<Utc>,
+    pub input_currency: Option<String>,
+    pub spot_price_usd: Option<Decimal>,
+    pub spot_source: Option<String>,
+    pub spot_at: Option<DateTime<Utc>>,
     pub spot_quote_id: Option<Uuid>,
     pub spot_from_cache: bool,
+    pub archived_at: Option<DateTime<Utc>>,
+    pub pooled_stake_github_user_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ChallengeSummaryRow {
+    pub id: Uuid,
+    pub github_pr_number: i32,
+    pub github_pr_author_id: i64,
+    pub github_pr_author_login: String,
+    pub head_sha: String,
+    pub status: String,
+    pub deadline_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ChallengeProofRow {
+    pub id: Uuid,
+    pub github_repo_id: i64,
+    pub github_pr_number: i32,
+    pub github_pr_author_id: i64,
+    pub head_sha: String,
+    pub status: String,
+    pub signature: Option<String>,
+    pub signer_address: Option<String>,
+    pub typed_data: Option<serde_json::Value>,
+    pub verified_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -24,6 +53,7 @@ pub struct SpotQuoteRow {
     pub source: String,
     pub price: Decimal,
     pub fetched_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -48,6 +78,26 @@ pub struct CurrentUserRow {
     pub github_user_id: i64,
     pub github_login: String,
     pub github_access_token: Option<String>,
+    pub session_created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct UserSessionRow {
+    pub id: Uuid,
+    pub session_token: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ServiceBotKeyRow {
+    pub key_id: String,
+    pub active: bool,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -0,0 +1,202 @@
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    config::Config,
+    error::{ApiError, ApiResult},
+};
+
+/// Every action the bot worker knows how to execute. Adding a type means adding a variant here,
+/// its payload schema below, and a handler in the worker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionType {
+    UpsertPrComment,
+    ClosePrWithComment,
+}
+
+// Schemas are checked by deserializing; not every field is read back.
+#[allow(dead_code)]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UpsertPrCommentPayload {
+    comment_markdown: String,
+    comment_marker: String,
+    reason: String,
+}
+
+#[allow(dead_code)]
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClosePrWithCommentPayload {
+    comment_markdown: String,
+    comment_marker: String,
+    reason: String,
+    #[serde(default)]
+    extra: Option<Value>,
+}
+
+impl ActionType {
+    /// Value stored in `bot_actions.action_type` and sent to workers.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::UpsertPrComment => "UPSERT_PR_COMMENT",
+            Self::ClosePrWithComment => "CLOSE_PR_WITH_COMMENT",
+        }
+    }
+
+    /// Rejects a payload that does not match this type's schema, so a malformed action never
+    /// reaches a worker.
+    pub fn validate_payload(self, payload: &Value) -> ApiResult<()> {
+        let (comment_markdown, comment_marker) = match self {
+            Self::UpsertPrComment => {
+                let p: UpsertPrCommentPayload = self.parse(payload)?;
+                (p.comment_markdown, p.comment_marker)
+            }
+            Self::ClosePrWithComment => {
+                let p: ClosePrWithCommentPayload = self.parse(payload)?;
+                (p.comment_markdown, p.comment_marker)
+            }
+        };
+        if comment_markdown.trim().is_empty() || comment_marker.trim().is_empty() {
+            return Err(self.invalid("comment_markdown and comment_marker must be non-empty"));
+        }
+        Ok(())
+    }
+
+    fn parse<T: serde::de::DeserializeOwned>(self, payload: &Value) -> ApiResult<T> {
+        T::deserialize(payload).map_err(|err| self.invalid(err))
+    }
+
+    fn invalid(self, reason: impl std::fmt::Display) -> ApiError {
+        ApiError::Internal(anyhow::anyhow!(
+            "invalid {} payload: {reason}",
+            self.as_str()
+        ))
+    }
+}
+
+/// Cuts `markdown` to at most `max_chars` characters, ending in an ellipsis when shortened.
+pub fn truncate_comment_markdown(markdown: &str, max_chars: usize) -> String {
+    if markdown.chars().count() <= max_chars {
+        return markdown.to_string();
+    }
+    let mut truncated: String = markdown.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Validates `payload` against `action_type` and queues a `PENDING` action, with
+/// `comment_markdown` truncated to `COMMENT_MAX_CHARS` so workers never post an oversized body.
+/// Returns whether a row was inserted; an identical pending action for the challenge already
+/// existing is not an error.
+#[allow(clippy::too_many_arguments)]
+pub async fn enqueue_bot_action(
+    executor: impl sqlx::PgExecutor<'_>,
+    config: &Config,
+    action_type: ActionType,
+    challenge_id: Option<Uuid>,
+    installation_id: i64,
+    github_repo_id: i64,
+    repo_full_name: &str,
+    github_pr_number: i32,
+    mut payload: Value,
+) -> ApiResult<bool> {
+    action_type.validate_payload(&payload)?;
+    if let Some(Value::String(markdown)) = payload.get_mut("comment_markdown") {
+        *markdown = truncate_comment_markdown(markdown, config.comment_max_chars);
+    }
+    let inserted = sqlx::query(
+        r#"
+        insert into bot_actions (
+          id, action_type, challenge_id, installation_id, github_repo_id, repo_full_name, github_pr_number, payload, status, claimed_at, completed_at, created_at, updated_at
+        )
+        values ($1, $2, $3, $4, $5, $6, $7, $8, 'PENDING', null, null, $9, $9)
+        on conflict do nothing
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(action_type.as_str())
+    .bind(challenge_id)
+    .bind(installation_id)
+    .bind(github_repo_id)
+    .bind(repo_full_name)
+    .bind(github_pr_number)
+    .bind(payload)
+    .bind(Utc::now())
+    .execute(executor)
+    .await?;
+    Ok(inserted.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn accepts_well_formed_payloads() {
+        ActionType::UpsertPrComment
+            .validate_payload(&json!({
+                "comment_markdown": "Please verify.",
+                "comment_marker": "sitg:gate:1",
+                "reason": "REQUIRE_STAKE"
+            }))
+            .expect("upsert payload");
+        ActionType::ClosePrWithComment
+            .validate_payload(&json!({
+                "comment_markdown": "Closing.",
+                "comment_marker": "sitg:timeout:1",
+                "reason": "CHALLENGE_TIMEOUT",
+                "extra": {"source": "deadline_sweeper"}
+            }))
+            .expect("close payload");
+    }
+
+    #[test]
+    fn truncates_comment_markdown_at_the_limit() {
+        assert_eq!(truncate_comment_markdown("abcde", 5), "abcde");
+        assert_eq!(truncate_comment_markdown("abcdef", 5), "abcd…");
+        assert_eq!(truncate_comment_markdown("abcdef", 5).chars().count(), 5);
+        // Counts characters, not bytes, and never splits one.
+        assert_eq!(truncate_comment_markdown("ééééé", 5), "ééééé");
+        assert_eq!(truncate_comment_markdown("éééééé", 5), "éééé…");
+        assert_eq!(truncate_comment_markdown("abc", 1), "…");
+    }
+
+    #[test]
+    fn rejects_malformed_payloads() {
+        let missing_marker =
+            json!({"comment_markdown": "Please verify.", "reason": "REQUIRE_STAKE"});
+        let empty_markdown = json!({
+            "comment_markdown": " ",
+            "comment_marker": "sitg:gate:1",
+            "reason": "REQUIRE_STAKE"
+        });
+        for action_type in [ActionType::UpsertPrComment, ActionType::ClosePrWithComment] {
+            for payload in [&missing_marker, &empty_markdown, &json!("not an object")] {
+                let err = action_type
+                    .validate_payload(payload)
+                    .expect_err("malformed payload");
+                assert!(
+                    matches!(err, ApiError::Internal(e) if e.to_string().contains(action_type.as_str()))
+                );
+            }
+        }
+
+        // `extra` belongs to the close schema only.
+        let with_extra = json!({
+            "comment_markdown": "Please verify.",
+            "comment_marker": "sitg:gate:1",
+            "reason": "REQUIRE_STAKE",
+            "extra": {}
+        });
+        assert!(
+            ActionType::UpsertPrComment
+                .validate_payload(&with_extra)
+                .is_err()
+        );
+    }
+}
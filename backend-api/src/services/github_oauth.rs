@@ -1,18 +1,62 @@
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 
 use crate::{
     config::Config,
     error::{ApiError, ApiResult},
+    services::http_client::outbound_client_builder,
 };
 
+const GITHUB_WEB_BASE_URL: &str = "https://github.com";
+const GITHUB_API_BASE_URL: &str = "https://api.github.com";
+
 #[derive(Clone)]
 pub struct GithubOAuthService {
     client: reqwest::Client,
+    web_base_url: String,
+    api_base_url: String,
+}
+
+/// Requested scopes not covered by `granted`. A broader granted scope covers the narrower ones
+/// it includes, e.g. `repo` covers `public_repo`.
+fn missing_scopes(requested: &[String], granted: &str) -> Vec<String> {
+    let granted: Vec<&str> = granted
+        .split([',', ' '])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    let covers = |scope: &str| {
+        granted.iter().any(|g| {
+            *g == scope
+                || matches!(
+                    (*g, scope),
+                    ("repo", "public_repo")
+                        | ("user", "read:user" | "user:email")
+                        | ("write:org" | "admin:org", "read:org")
+                )
+        })
+    };
+    requested
+        .iter()
+        .filter(|scope| !covers(scope))
+        .cloned()
+        .collect()
 }
 
+/// GitHub answers a rejected exchange with `200 OK` and an `error` body rather than a 4xx.
 #[derive(Debug, Deserialize)]
-struct GithubAccessTokenResponse {
-    access_token: String,
+#[serde(untagged)]
+enum GithubAccessTokenResponse {
+    Token {
+        access_token: String,
+        /// Comma-separated scopes the user actually granted.
+        scope: Option<String>,
+    },
+    Error {
+        error: String,
+        error_description: Option<String>,
+    },
 }
 
 #[derive(Debug, Deserialize)]
@@ -61,26 +105,63 @@ impl GithubOAuthService {
             .unwrap_or(false)
     }
 
-    pub fn new() -> Self {
+    /// Every request carries the configured `User-Agent` and a pinned `X-GitHub-Api-Version`.
+    pub fn new(config: &Config) -> Self {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Ok(user_agent) = reqwest::header::HeaderValue::from_str(&config.github_user_agent) {
+            headers.insert(reqwest::header::USER_AGENT, user_agent);
+        }
+        if let Ok(version) = reqwest::header::HeaderValue::from_str(&config.github_api_version) {
+            headers.insert("X-GitHub-Api-Version", version);
+        }
+        let client = outbound_client_builder(config)
+            .default_headers(headers)
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
         Self {
-            client: reqwest::Client::new(),
+            client,
+            web_base_url: GITHUB_WEB_BASE_URL.to_string(),
+            api_base_url: GITHUB_API_BASE_URL.to_string(),
         }
     }
 
-    pub fn authorize_url(&self, config: &Config, state: &str) -> ApiResult<String> {
+    #[cfg(test)]
+    pub(crate) fn with_base_url(mut self, base_url: &str) -> Self {
+        self.web_base_url = base_url.to_string();
+        self.api_base_url = base_url.to_string();
+        self
+    }
+
+    /// `code_challenge` is the S256 PKCE challenge, sent only when PKCE is enabled.
+    pub fn authorize_url(
+        &self,
+        config: &Config,
+        state: &str,
+        code_challenge: Option<&str>,
+    ) -> ApiResult<String> {
         let client_id = config
             .github_client_id
             .as_deref()
             .ok_or_else(|| ApiError::validation("GITHUB_CLIENT_ID is not configured"))?;
         let redirect_uri = format!("{}/api/v1/auth/github/callback", config.api_base_url);
         let encoded_redirect = urlencoding::encode(&redirect_uri);
-        let encoded_scope = urlencoding::encode("read:user public_repo");
+        let scope = config.github_oauth_scopes.join(" ");
+        let encoded_scope = urlencoding::encode(&scope);
+        let pkce = code_challenge
+            .map(|challenge| format!("&code_challenge={challenge}&code_challenge_method=S256"))
+            .unwrap_or_default();
         Ok(format!(
-            "https://github.com/login/oauth/authorize?client_id={client_id}&redirect_uri={encoded_redirect}&scope={encoded_scope}&state={state}"
+            "{}/login/oauth/authorize?client_id={client_id}&redirect_uri={encoded_redirect}&scope={encoded_scope}{pkce}&state={state}",
+            self.web_base_url
         ))
     }
 
-    pub async fn exchange_code_for_token(&self, config: &Config, code: &str) -> ApiResult<String> {
+    pub async fn exchange_code_for_token(
+        &self,
+        config: &Config,
+        code: &str,
+        code_verifier: Option<&str>,
+    ) -> ApiResult<String> {
         let client_id = config
             .github_client_id
             .as_deref()
@@ -90,15 +171,20 @@ impl GithubOAuthService {
             .as_deref()
             .ok_or_else(|| ApiError::validation("GITHUB_CLIENT_SECRET is not configured"))?;
 
+        let mut body = serde_json::json!({
+            "client_id": client_id,
+            "client_secret": client_secret,
+            "code": code,
+        });
+        if let Some(code_verifier) = code_verifier {
+            body["code_verifier"] = serde_json::Value::from(code_verifier);
+        }
+
         let response = self
             .client
-            .post("https://github.com/login/oauth/access_token")
+            .post(format!("{}/login/oauth/access_token", self.web_base_url))
             .header("Accept", "application/json")
-            .json(&serde_json::json!({
-                "client_id": client_id,
-                "client_secret": client_secret,
-                "code": code,
-            }))
+            .json(&body)
             .send()
             .await
             .map_err(|e| ApiError::Internal(e.into()))?;
@@ -112,15 +198,47 @@ impl GithubOAuthService {
             .await
             .map_err(|e| ApiError::Internal(e.into()))?;
 
-        Ok(payload.access_token)
+        match payload {
+            GithubAccessTokenResponse::Token {
+                access_token,
+                scope,
+            } => {
+                if let Some(granted) = scope {
+                    let missing = missing_scopes(&config.github_oauth_scopes, &granted);
+                    if !missing.is_empty() {
+                        tracing::warn!(
+                            granted = %granted,
+                            missing = ?missing,
+                            "GitHub login granted fewer scopes than requested"
+                        );
+                        return Err(ApiError::validation(format!(
+                            "GitHub did not grant the required scopes ({}); sign in again and approve them",
+                            missing.join(", ")
+                        )));
+                    }
+                }
+                Ok(access_token)
+            }
+            GithubAccessTokenResponse::Error { error, .. } if error == "bad_verification_code" => {
+                Err(ApiError::validation(
+                    "GitHub authorization code is invalid or expired; sign in again",
+                ))
+            }
+            GithubAccessTokenResponse::Error {
+                error,
+                error_description,
+            } => Err(ApiError::Internal(anyhow::anyhow!(
+                "GitHub token exchange rejected: {error} ({})",
+                error_description.unwrap_or_default()
+            ))),
+        }
     }
 
     pub async fn fetch_user(&self, access_token: &str) -> ApiResult<GithubUserResponse> {
         let response = self
             .client
-            .get("https://api.github.com/user")
+            .get(format!("{}/user", self.api_base_url))
             .bearer_auth(access_token)
-            .header("User-Agent", "sitg-backend")
             .send()
             .await
             .map_err(|e| ApiError::Internal(e.into()))?;
@@ -136,10 +254,35 @@ impl GithubOAuthService {
     }
 
     pub async fn resolve_login(&self, login: &str) -> ApiResult<Option<GithubUserResponse>> {
-        let response = self
-            .client
-            .get(format!("https://api.github.com/users/{login}"))
-            .header("User-Agent", "sitg-backend")
+        self.lookup_user(&format!("{}/users/{login}", self.api_base_url), None)
+            .await
+    }
+
+    /// Looks an account up by its stable numeric id; `None` once the account is deleted. Runs
+    /// under the caller's token so bulk lookups spend their rate limit, not the shared
+    /// unauthenticated one.
+    pub async fn resolve_user_id(
+        &self,
+        token: &str,
+        github_user_id: i64,
+    ) -> ApiResult<Option<GithubUserResponse>> {
+        self.lookup_user(
+            &format!("{}/user/{github_user_id}", self.api_base_url),
+            Some(token),
+        )
+        .await
+    }
+
+    async fn lookup_user(
+        &self,
+        url: &str,
+        token: Option<&str>,
+    ) -> ApiResult<Option<GithubUserResponse>> {
+        let mut request = self.client.get(url);
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        let response = request
             .send()
             .await
             .map_err(|e| ApiError::Internal(e.into()))?;
@@ -167,10 +310,10 @@ impl GithubOAuthService {
         let response = self
             .client
             .get(format!(
-                "https://api.github.com/repos/{full_repo_name}/collaborators/{login}/permission"
+                "{}/repos/{full_repo_name}/collaborators/{login}/permission",
+                self.api_base_url
             ))
             .bearer_auth(token)
-            .header("User-Agent", "sitg-backend")
             .send()
             .await
             .map_err(|e| ApiError::Internal(e.into()))?;
@@ -201,9 +344,11 @@ impl GithubOAuthService {
     pub async fn list_writable_repos(&self, token: &str) -> ApiResult<Vec<GithubRepoOption>> {
         let response = self
             .client
-            .get("https://api.github.com/user/repos?per_page=100&sort=updated&affiliation=owner,collaborator,organization_member")
+            .get(format!(
+                "{}/user/repos?per_page=100&sort=updated&affiliation=owner,collaborator,organization_member",
+                self.api_base_url
+            ))
             .bearer_auth(token)
-            .header("User-Agent", "sitg-backend")
             .send()
             .await
             .map_err(|e| ApiError::Internal(e.into()))?;
@@ -229,7 +374,7 @@ impl GithubOAuthService {
             })
             .collect();
 
-        out.sort_by(|a, b| a.full_name.to_lowercase().cmp(&b.full_name.to_lowercase()));
+        out.sort_by_key(|repo| repo.full_name.to_lowercase());
         Ok(out)
     }
 
@@ -240,9 +385,8 @@ impl GithubOAuthService {
     ) -> ApiResult<Option<GithubRepoLookup>> {
         let response = self
             .client
-            .get(format!("https://api.github.com/repositories/{repo_id}"))
+            .get(format!("{}/repositories/{repo_id}", self.api_base_url))
             .bearer_auth(token)
-            .header("User-Agent", "sitg-backend")
             .send()
             .await
             .map_err(|e| ApiError::Internal(e.into()))?;
@@ -269,24 +413,21 @@ impl GithubOAuthService {
     }
 }
 
+/// RFC 7636 S256 challenge: base64url (unpadded) of the verifier's SHA-256.
+pub fn pkce_code_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn test_config(client_id: Option<&str>) -> Config {
         Config {
-            host: "0.0.0.0".to_string(),
-            port: 8080,
-            database_url: "postgres://localhost/sitg".to_string(),
-            db_max_connections: 10,
-            app_base_url: "https://sitg.io".to_string(),
             api_base_url: "https://api.sitg.io".to_string(),
             github_client_id: client_id.map(str::to_string),
             github_client_secret: Some("secret".to_string()),
-            session_cookie_name: "sitg_session".to_string(),
-            blocked_unlink_wallets: vec![],
-            base_rpc_url: None,
-            staking_contract_address: None,
+            ..Config::for_tests()
         }
     }
 
@@ -322,18 +463,18 @@ mod tests {
 
     #[test]
     fn authorize_url_requires_client_id() {
-        let service = GithubOAuthService::new();
+        let service = GithubOAuthService::new(&Config::for_tests());
         let err = service
-            .authorize_url(&test_config(None), "state-123")
+            .authorize_url(&test_config(None), "state-123", None)
             .expect_err("missing client id should fail");
         assert!(matches!(err, ApiError::Validation(msg) if msg.contains("GITHUB_CLIENT_ID")));
     }
 
     #[test]
     fn authorize_url_encodes_callback_scope_and_state() {
-        let service = GithubOAuthService::new();
+        let service = GithubOAuthService::new(&Config::for_tests());
         let url = service
-            .authorize_url(&test_config(Some("client-123")), "state-123")
+            .authorize_url(&test_config(Some("client-123")), "state-123", None)
             .expect("authorize URL");
         assert!(url.contains("client_id=client-123"));
         assert!(url.contains(
@@ -342,4 +483,182 @@ mod tests {
         assert!(url.contains("scope=read%3Auser%20public_repo"));
         assert!(url.ends_with("&state=state-123"));
     }
+
+    #[test]
+    fn authorize_url_uses_configured_scopes() {
+        let service = GithubOAuthService::new(&Config::for_tests());
+        let config = Config {
+            github_oauth_scopes: vec!["read:user".to_string(), "repo".to_string()],
+            ..test_config(Some("client-123"))
+        };
+        let url = service
+            .authorize_url(&config, "state-123", None)
+            .expect("authorize URL");
+        assert!(url.contains("scope=read%3Auser%20repo&"));
+    }
+
+    #[test]
+    fn pkce_code_challenge_matches_rfc_7636_vector() {
+        assert_eq!(
+            pkce_code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn authorize_url_carries_pkce_challenge_when_given() {
+        let service = GithubOAuthService::new(&Config::for_tests());
+        let url = service
+            .authorize_url(
+                &test_config(Some("client-123")),
+                "state-123",
+                Some("challenge-abc"),
+            )
+            .expect("authorize URL");
+        assert!(url.contains("&code_challenge=challenge-abc&code_challenge_method=S256&"));
+        assert!(url.ends_with("&state=state-123"));
+    }
+
+    /// Serves `app` locally and returns its base URL, standing in for github.com and its API.
+    async fn spawn_github_stub(app: axum::Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind github stub");
+        let addr = listener.local_addr().expect("github stub addr");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve github stub");
+        });
+        format!("http://{addr}")
+    }
+
+    #[tokio::test]
+    async fn sends_configured_user_agent_and_api_version() {
+        use std::sync::{Arc, Mutex};
+
+        use axum::{Json, Router, http::HeaderMap, routing::get};
+
+        let seen: Arc<Mutex<Option<HeaderMap>>> = Arc::default();
+        let captured = seen.clone();
+        let app = Router::new().route(
+            "/user",
+            get(move |headers: HeaderMap| async move {
+                *captured.lock().expect("headers lock") = Some(headers);
+                Json(serde_json::json!({"id": 1, "login": "octocat"}))
+            }),
+        );
+        let base_url = spawn_github_stub(app).await;
+
+        let config = Config {
+            github_user_agent: "sitg-test/1.0".to_string(),
+            github_api_version: "2026-03-10".to_string(),
+            ..Config::for_tests()
+        };
+        let service = GithubOAuthService::new(&config).with_base_url(&base_url);
+        let user = service.fetch_user("token").await.expect("user");
+        assert_eq!(user.login, "octocat");
+
+        let headers = seen
+            .lock()
+            .expect("headers lock")
+            .take()
+            .expect("request seen");
+        assert_eq!(headers["user-agent"], "sitg-test/1.0");
+        assert_eq!(headers["x-github-api-version"], "2026-03-10");
+        assert_eq!(headers["authorization"], "Bearer token");
+    }
+
+    #[tokio::test]
+    async fn token_exchange_reports_rejected_code_distinctly() {
+        use axum::{Json, Router, routing::post};
+
+        let app = Router::new().route(
+            "/login/oauth/access_token",
+            post(|| async {
+                Json(serde_json::json!({
+                    "error": "bad_verification_code",
+                    "error_description": "The code passed is incorrect or expired.",
+                    "error_uri": "https://docs.github.com/apps/troubleshooting"
+                }))
+            }),
+        );
+        let base_url = spawn_github_stub(app).await;
+
+        let config = test_config(Some("client-123"));
+        let service = GithubOAuthService::new(&config).with_base_url(&base_url);
+        let err = service
+            .exchange_code_for_token(&config, "stale-code", None)
+            .await
+            .expect_err("rejected code");
+        assert!(matches!(err, ApiError::Validation(msg) if msg.contains("invalid or expired")));
+    }
+
+    #[test]
+    fn reports_requested_scopes_missing_from_the_grant() {
+        let requested = vec!["read:user".to_string(), "public_repo".to_string()];
+        assert!(missing_scopes(&requested, "read:user,public_repo").is_empty());
+        assert!(missing_scopes(&requested, "repo, user").is_empty());
+        assert_eq!(missing_scopes(&requested, "read:user"), vec!["public_repo"]);
+        assert_eq!(missing_scopes(&requested, ""), requested);
+    }
+
+    #[tokio::test]
+    async fn token_exchange_rejects_a_reduced_scope_grant() {
+        use axum::{Json, Router, routing::post};
+
+        let app = Router::new().route(
+            "/login/oauth/access_token",
+            post(|| async {
+                Json(serde_json::json!({
+                    "access_token": "gho_reduced",
+                    "scope": "read:user",
+                    "token_type": "bearer"
+                }))
+            }),
+        );
+        let base_url = spawn_github_stub(app).await;
+
+        let config = test_config(Some("client-123"));
+        let service = GithubOAuthService::new(&config).with_base_url(&base_url);
+        let err = service
+            .exchange_code_for_token(&config, "code", None)
+            .await
+            .expect_err("public_repo was not granted");
+        assert!(matches!(err, ApiError::Validation(msg) if msg.contains("public_repo")));
+    }
+
+    #[tokio::test]
+    async fn token_exchange_sends_code_verifier_only_when_given() {
+        use std::sync::{Arc, Mutex};
+
+        use axum::{Json, Router, routing::post};
+
+        let bodies: Arc<Mutex<Vec<serde_json::Value>>> = Arc::default();
+        let captured = bodies.clone();
+        let app = Router::new().route(
+            "/login/oauth/access_token",
+            post(move |Json(body): Json<serde_json::Value>| async move {
+                captured.lock().expect("bodies lock").push(body);
+                Json(serde_json::json!({"access_token": "gho_test"}))
+            }),
+        );
+        let base_url = spawn_github_stub(app).await;
+
+        let config = test_config(Some("client-123"));
+        let service = GithubOAuthService::new(&config).with_base_url(&base_url);
+        let token = service
+            .exchange_code_for_token(&config, "code-1", Some("verifier-1"))
+            .await
+            .expect("exchange with verifier");
+        assert_eq!(token, "gho_test");
+        service
+            .exchange_code_for_token(&config, "code-2", None)
+            .await
+            .expect("exchange without verifier");
+
+        let bodies = bodies.lock().expect("bodies lock");
+        assert_eq!(bodies[0]["code"], "code-1");
+        assert_eq!(bodies[0]["code_verifier"], "verifier-1");
+        assert_eq!(bodies[1]["code"], "code-2");
+        assert!(bodies[1].get("code_verifier").is_none());
+    }
 }
@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use crate::config::Config;
+
+/// Builder for clients calling out to GitHub, the RPC node, price feeds and webhook
+/// subscribers, with the connection pool tuned by `HTTP_POOL_MAX_IDLE_PER_HOST` and
+/// `HTTP_POOL_IDLE_TIMEOUT_SECONDS`. Callers add their own timeouts and headers.
+pub fn outbound_client_builder(config: &Config) -> reqwest::ClientBuilder {
+    let mut builder = reqwest::Client::builder().pool_idle_timeout(
+        (config.http_pool_idle_timeout_seconds > 0)
+            .then(|| Duration::from_secs(config.http_pool_idle_timeout_seconds)),
+    );
+    if let Some(max_idle) = config.http_pool_max_idle_per_host {
+        builder = builder.pool_max_idle_per_host(max_idle);
+    }
+    builder
+}
+
+/// `outbound_client_builder` with nothing added.
+pub fn outbound_client(config: &Config) -> reqwest::Client {
+    outbound_client_builder(config)
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn builds_clients_with_the_configured_pool() {
+        use axum::{Router, routing::get};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind stub");
+        let addr = listener.local_addr().expect("stub addr");
+        tokio::spawn(async move {
+            axum::serve(listener, Router::new().route("/", get(|| async { "ok" })))
+                .await
+                .expect("serve stub");
+        });
+
+        for (max_idle, idle_timeout) in [(Some(0), 0), (Some(4), 30), (None, 90)] {
+            let config = Config {
+                http_pool_max_idle_per_host: max_idle,
+                http_pool_idle_timeout_seconds: idle_timeout,
+                ..Config::for_tests()
+            };
+            let client = outbound_client_builder(&config)
+                .timeout(Duration::from_secs(5))
+                .build()
+                .expect("client builds");
+            for _ in 0..2 {
+                let body = client
+                    .get(format!("http://{addr}/"))
+                    .send()
+                    .await
+                    .expect("request")
+                    .text()
+                    .await
+                    .expect("body");
+                assert_eq!(body, "ok");
+            }
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use sha3::{Digest, Keccak256};
+
+use crate::{
+    config::Config,
+    error::{ApiError, ApiResult},
+    services::http_client::outbound_client,
+};
+
+/// Resolves a wallet's primary (reverse) ENS-style name through a reverse resolver contract.
+#[derive(Clone)]
+pub struct IdentityService {
+    client: Client,
+    rpc_url: Option<String>,
+    reverse_resolver_address: Option<String>,
+    reverse_namespace: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct EthCallResponse {
+    result: String,
+}
+
+impl IdentityService {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            client: outbound_client(config),
+            // Name lookups are best-effort, so they stay on the primary endpoint.
+            rpc_url: config.base_rpc_urls.first().cloned(),
+            reverse_resolver_address: config.ens_reverse_resolver_address.clone(),
+            reverse_namespace: config.ens_reverse_namespace.clone(),
+        }
+    }
+
+    /// Whether wallet identity lookups are configured at all.
+    pub fn enabled(&self) -> bool {
+        self.reverse_resolver_address.is_some()
+    }
+
+    /// Calls `name(bytes32)` on the reverse resolver for `<address>.<namespace>`; an empty name
+    /// means the wallet has no primary name.
+    pub async fn primary_name(&self, wallet_address: &str) -> ApiResult<Option<String>> {
+        let rpc_url = self
+            .rpc_url
+            .as_ref()
+            .ok_or_else(|| ApiError::validation("BASE_RPC_URL is not configured"))?;
+        let resolver = self.reverse_resolver_address.as_ref().ok_or_else(|| {
+            ApiError::validation("ENS_REVERSE_RESOLVER_ADDRESS is not configured")
+        })?;
+
+        let address = wallet_address
+            .trim()
+            .trim_start_matches("0x")
+            .to_lowercase();
+        let node = namehash(&format!("{address}.{}", self.reverse_namespace));
+        let selector = Keccak256::digest(b"name(bytes32)");
+        let data = format!("0x{}{}", hex::encode(&selector[0..4]), hex::encode(node));
+        let body = json!({
+          "jsonrpc": "2.0",
+          "id": 1,
+          "method": "eth_call",
+          "params": [{"to": resolver, "data": data}, "latest"]
+        });
+
+        let response = self
+            .client
+            .post(rpc_url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| ApiError::Internal(e.into()))?;
+        if !response.status().is_success() {
+            return Err(ApiError::validation("identity RPC call failed"));
+        }
+        let payload: EthCallResponse = response
+            .json()
+            .await
+            .map_err(|e| ApiError::Internal(e.into()))?;
+
+        let name = decode_abi_string(&payload.result)?;
+        Ok(Some(name).filter(|name| !name.is_empty()))
+    }
+}
+
+/// ENS namehash (EIP-137).
+fn namehash(name: &str) -> [u8; 32] {
+    let mut node = [0u8; 32];
+    for label in name.rsplit('.').filter(|label| !label.is_empty()) {
+        let mut hasher = Keccak256::new();
+        hasher.update(node);
+        hasher.update(Keccak256::digest(label.as_bytes()));
+        node.copy_from_slice(&hasher.finalize());
+    }
+    node
+}
+
+/// Decodes a single ABI-encoded `string` return value. An empty `0x` result (no resolver code
+/// or a reverted call on some nodes) is treated as an empty name.
+fn decode_abi_string(hex_value: &str) -> ApiResult<String> {
+    let invalid = || ApiError::validation("identity RPC returned a malformed name");
+    let bytes = hex::decode(hex_value.trim_start_matches("0x")).map_err(|_| invalid())?;
+    if bytes.is_empty() {
+        return Ok(String::new());
+    }
+    let word = |at: usize| -> ApiResult<usize> {
+        let chunk = bytes.get(at..at + 32).ok_or_else(invalid)?;
+        if chunk[..24].iter().any(|b| *b != 0) {
+            return Err(invalid());
+        }
+        Ok(u64::from_be_bytes(chunk[24..].try_into().map_err(|_| invalid())?) as usize)
+    };
+    let offset = word(0)?;
+    let len = word(offset)?;
+    let start = offset + 32;
+    let raw = bytes.get(start..start + len).ok_or_else(invalid)?;
+    String::from_utf8(raw.to_vec()).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abi_string(value: &str) -> String {
+        let mut out = vec![0u8; 64];
+        out[31] = 32;
+        out[56..64].copy_from_slice(&(value.len() as u64).to_be_bytes());
+        let mut data = value.as_bytes().to_vec();
+        data.resize(value.len().div_ceil(32) * 32, 0);
+        out.extend(data);
+        format!("0x{}", hex::encode(out))
+    }
+
+    /// JSON-RPC stub answering every `eth_call` with `result`.
+    async fn spawn_resolver_rpc(result: String) -> String {
+        use axum::{Json, Router, routing::post};
+
+        let app = Router::new().route(
+            "/",
+            post(move || async move { Json(json!({"jsonrpc": "2.0", "id": 1, "result": result})) }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind rpc stub");
+        let addr = listener.local_addr().expect("rpc stub addr");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve rpc stub");
+        });
+        format!("http://{addr}")
+    }
+
+    fn service(rpc_url: String) -> IdentityService {
+        IdentityService::new(&Config {
+            base_rpc_urls: vec![rpc_url],
+            ens_reverse_resolver_address: Some(
+                "0x3333333333333333333333333333333333333333".to_string(),
+            ),
+            ..Config::for_tests()
+        })
+    }
+
+    #[test]
+    fn computes_ens_namehash() {
+        assert_eq!(namehash(""), [0u8; 32]);
+        assert_eq!(
+            hex::encode(namehash("eth")),
+            "93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+        );
+        assert_eq!(
+            hex::encode(namehash("foo.eth")),
+            "de9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f"
+        );
+    }
+
+    #[tokio::test]
+    async fn resolves_primary_name_when_present() {
+        let rpc_url = spawn_resolver_rpc(abi_string("alice.eth")).await;
+        let name = service(rpc_url)
+            .primary_name("0x1111111111111111111111111111111111111111")
+            .await
+            .expect("lookup");
+        assert_eq!(name.as_deref(), Some("alice.eth"));
+    }
+
+    #[tokio::test]
+    async fn reports_no_name_when_absent() {
+        let rpc_url = spawn_resolver_rpc(abi_string("")).await;
+        let name = service(rpc_url)
+            .primary_name("0x1111111111111111111111111111111111111111")
+            .await
+            .expect("lookup");
+        assert_eq!(name, None);
+    }
+}
@@ -0,0 +1,27 @@
+use sqlx::PgPool;
+
+/// Installation that currently serves a repository according to the GitHub App mapping.
+///
+/// Repos can move between installations after their config was saved, so anything that talks to
+/// GitHub on a repo's behalf should resolve the installation here rather than trusting
+/// `repo_configs.installation_id`.
+pub async fn current_installation_id(
+    pool: &PgPool,
+    github_repo_id: i64,
+) -> Result<Option<i64>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        select gir.installation_id
+        from github_installation_repositories gir
+        join github_installations i on i.installation_id = gir.installation_id
+        where gir.github_repo_id = $1
+          and gir.active = true
+          and i.active = true
+        order by gir.updated_at desc
+        limit 1
+        "#,
+    )
+    .bind(github_repo_id)
+    .fetch_optional(pool)
+    .await
+}
@@ -6,7 +6,7 @@ use sqlx::PgPool;
 use crate::error::{ApiError, ApiResult};
 
 pub struct InternalAuthContext {
-    pub _key_id: String,
+    pub key_id: String,
     pub timestamp: i64,
     pub signature_hex: String,
 }
@@ -56,7 +56,7 @@ pub async fn verify_internal_request(
         .await?;
 
     Ok(InternalAuthContext {
-        _key_id: key_id.to_string(),
+        key_id: key_id.to_string(),
         timestamp,
         signature_hex,
     })
@@ -1,58 +1,155 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
 
 use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::Rng;
 use serde_json::{Value, json};
+use sha2::Sha256;
 use uuid::Uuid;
 
-use crate::{app::AppState, error::ApiResult};
+use crate::{
+    app::AppState,
+    config::{AuditRetentionMode, Config},
+    error::ApiResult,
+    routes::replay_deferred_pr_events,
+    services::{
+        bot_actions::{ActionType, enqueue_bot_action},
+        http_client::outbound_client_builder,
+        installations::current_installation_id,
+        leader::{JOBS_LEADER_LOCK_KEY, run_leader_election},
+        outbound_webhooks::deliver_due_events,
+    },
+};
+
+/// How often a standby replica retries for leadership and the leader checks it still holds it.
+const LEADER_ELECTION_INTERVAL: Duration = Duration::from_secs(15);
 
 pub fn start_background_jobs(state: Arc<AppState>) {
+    // The deadline sweeper and retention cleanup run on one replica at a time; outbound delivery
+    // claims rows with `skip locked` and runs everywhere.
+    let is_leader = Arc::new(AtomicBool::new(false));
+    tokio::spawn(run_leader_election(
+        state.pool.clone(),
+        JOBS_LEADER_LOCK_KEY,
+        LEADER_ELECTION_INTERVAL,
+        is_leader.clone(),
+    ));
+
     let state_for_deadlines = state.clone();
+    let leader_for_deadlines = is_leader.clone();
     tokio::spawn(async move {
-        run_deadline_loop(state_for_deadlines).await;
+        run_deadline_loop(state_for_deadlines, leader_for_deadlines).await;
     });
 
+    if state.config.outbound_webhook_url.is_some() {
+        let state_for_outbound = state.clone();
+        tokio::spawn(async move {
+            run_outbound_loop(state_for_outbound).await;
+        });
+    }
+
     tokio::spawn(async move {
-        run_retention_loop(state).await;
+        run_retention_loop(state, is_leader).await;
     });
 }
 
-async fn run_deadline_loop(state: Arc<AppState>) {
-    let mut ticker = tokio::time::interval(Duration::from_secs(60));
-    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+async fn run_outbound_loop(state: Arc<AppState>) {
+    let client = outbound_client_builder(&state.config)
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new());
+    let period = Duration::from_secs(30);
+    let jitter_percent = state.config.job_jitter_percent;
+    tokio::time::sleep(startup_delay(period, jitter_percent)).await;
+
+    loop {
+        if let Err(err) = deliver_due_events(&state, &client).await {
+            tracing::error!(error = %err, "outbound webhook iteration failed");
+        }
+        tokio::time::sleep(next_tick_delay(period, jitter_percent)).await;
+    }
+}
+
+async fn run_deadline_loop(state: Arc<AppState>, is_leader: Arc<AtomicBool>) {
+    let period = Duration::from_secs(60);
+    let jitter_percent = state.config.job_jitter_percent;
+    tokio::time::sleep(startup_delay(period, jitter_percent)).await;
 
     loop {
-        ticker.tick().await;
-        if let Err(err) = process_due_challenges(&state).await {
+        // Replicas that do not hold the jobs lock stand by.
+        if is_leader.load(Ordering::Relaxed)
+            && let Err(err) = process_due_challenges(&state).await
+        {
             tracing::error!(error = %err, "deadline loop iteration failed");
         }
+        tokio::time::sleep(next_tick_delay(period, jitter_percent)).await;
     }
 }
 
-async fn run_retention_loop(state: Arc<AppState>) {
-    let mut ticker = tokio::time::interval(Duration::from_secs(60 * 60 * 24));
-    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+async fn run_retention_loop(state: Arc<AppState>, is_leader: Arc<AtomicBool>) {
+    let period = Duration::from_secs(60 * 60 * 24);
+    let jitter_percent = state.config.job_jitter_percent;
+    tokio::time::sleep(startup_delay(period, jitter_percent)).await;
 
     loop {
-        ticker.tick().await;
-        if let Err(err) = cleanup_retention(&state).await {
+        // Replicas that do not hold the jobs lock stand by.
+        if is_leader.load(Ordering::Relaxed)
+            && let Err(err) = cleanup_retention(&state).await
+        {
             tracing::error!(error = %err, "retention cleanup iteration failed");
         }
+        tokio::time::sleep(next_tick_delay(period, jitter_percent)).await;
     }
 }
 
+/// Replicas started together would otherwise run every job in lockstep; each loop waits a random
+/// slice of `JOB_JITTER_PERCENT` of its period before the first run.
+fn startup_delay(period: Duration, jitter_percent: u32) -> Duration {
+    period.mul_f64(f64::from(jitter_percent) / 100.0 * rand::random::<f64>())
+}
+
+/// The period, randomly shortened or stretched by up to `JOB_JITTER_PERCENT`.
+fn next_tick_delay(period: Duration, jitter_percent: u32) -> Duration {
+    jittered_delay(
+        period,
+        jitter_percent,
+        rand::thread_rng().gen_range(-1.0..=1.0),
+    )
+}
+
+/// `sample` in `[-1, 1]` picks where in `period ± jitter_percent%` the delay lands.
+fn jittered_delay(period: Duration, jitter_percent: u32, sample: f64) -> Duration {
+    let spread = period.as_secs_f64() * f64::from(jitter_percent) / 100.0;
+    Duration::from_secs_f64((period.as_secs_f64() + spread * sample.clamp(-1.0, 1.0)).max(0.0))
+}
+
 async fn process_due_challenges(state: &AppState) -> ApiResult<()> {
     let due: Vec<Uuid> = sqlx::query_scalar(
-        "select id from pr_challenges where status = 'PENDING' and deadline_at <= $1 order by deadline_at asc limit 500",
+        r#"
+        select c.id from pr_challenges c
+        where c.status = 'PENDING' and c.deadline_at <= $1
+          and not exists (
+            select 1 from repo_configs r
+            where r.github_repo_id = c.github_repo_id and r.archived_at is not null
+          )
+        order by c.deadline_at asc
+        limit 500
+        "#,
     )
     .bind(Utc::now())
     .fetch_all(&state.pool)
     .await?;
 
     for challenge_id in due {
-        let challenge_meta: Option<(i64, i32, i64, String)> = sqlx::query_as(
+        let challenge_meta: Option<(i64, i32, String)> = sqlx::query_as(
             r#"
-            select c.github_repo_id, c.github_pr_number, r.installation_id, r.full_name
+            select c.github_repo_id, c.github_pr_number, r.full_name
             from pr_challenges c
             join repo_configs r on r.github_repo_id = c.github_repo_id
             where c.id = $1
@@ -62,7 +159,7 @@ async fn process_due_challenges(state: &AppState) -> ApiResult<()> {
         .fetch_optional(&state.pool)
         .await?;
 
-        let result = sqlx::query(
+        let new_status: Option<String> = sqlx::query_scalar(
             r#"
             update pr_challenges c
             set status = case
@@ -73,17 +170,16 @@ async fn process_due_challenges(state: &AppState) -> ApiResult<()> {
                                and w.github_user_id = c.github_pr_author_id
                            ) then 'EXEMPT'
                            else 'TIMED_OUT_CLOSED'
-                         end,
-                updated_at = $2
+                         end
             where c.id = $1 and c.status = 'PENDING'
+            returning c.status
             "#,
         )
         .bind(challenge_id)
-        .bind(Utc::now())
-        .execute(&state.pool)
+        .fetch_optional(&state.pool)
         .await?;
 
-        if result.rows_affected() > 0 {
+        if let Some(new_status) = new_status {
             sqlx::query(
                 "insert into audit_events (id, event_type, entity_type, entity_id, payload, created_at) values ($1, 'CHALLENGE_DEADLINE_SWEEP', 'challenge', $2, $3, $4)",
             )
@@ -94,8 +190,20 @@ async fn process_due_challenges(state: &AppState) -> ApiResult<()> {
             .execute(&state.pool)
             .await?;
 
-            if let Some((github_repo_id, github_pr_number, installation_id, repo_full_name)) =
-                challenge_meta
+            let installation_id = match &challenge_meta {
+                Some((github_repo_id, ..)) => {
+                    current_installation_id(&state.pool, *github_repo_id).await?
+                }
+                None => None,
+            };
+            // An author whitelisted after the challenge was opened is exempted, not closed.
+            if new_status != "TIMED_OUT_CLOSED" {
+                continue;
+            }
+            if let (
+                Some((github_repo_id, github_pr_number, repo_full_name)),
+                Some(installation_id),
+            ) = (challenge_meta, installation_id)
             {
                 queue_bot_action(
                     state,
@@ -104,7 +212,7 @@ async fn process_due_challenges(state: &AppState) -> ApiResult<()> {
                     github_repo_id,
                     &repo_full_name,
                     github_pr_number,
-                    "Stake verification was not completed within 30 minutes, so this PR has been closed.",
+                    &timeout_close_comment(&state.config),
                     json!({"source":"deadline_sweeper"}),
                 )
                 .await?;
@@ -112,9 +220,24 @@ async fn process_due_challenges(state: &AppState) -> ApiResult<()> {
         }
     }
 
+    // Timed-out challenges free slots under the pending challenge cap.
+    replay_deferred_pr_events(state).await?;
+
     Ok(())
 }
 
+/// Posted by the worker as it closes a timed-out PR, under its own `sitg:timeout:` marker so it
+/// never overwrites the gate comment. `TIMEOUT_CLOSE_COMMENT` replaces the default text.
+pub(crate) fn timeout_close_comment(config: &Config) -> String {
+    config.timeout_close_comment.clone().unwrap_or_else(|| {
+        format!(
+            "Stake verification was not completed within 30 minutes, so this PR has been closed.\n\nThe author can link a staked wallet at {} and reopen the PR to get a new verification window.",
+            config.app_base_url.trim_end_matches('/')
+        )
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn queue_bot_action(
     state: &AppState,
     challenge_id: Uuid,
@@ -132,24 +255,17 @@ async fn queue_bot_action(
       "reason": "CHALLENGE_TIMEOUT",
       "extra": extra_payload
     });
-    sqlx::query(
-        r#"
-        insert into bot_actions (
-          id, action_type, challenge_id, installation_id, github_repo_id, repo_full_name, github_pr_number, payload, status, claimed_at, completed_at, created_at, updated_at
-        )
-        values ($1, 'CLOSE_PR_WITH_COMMENT', $2, $3, $4, $5, $6, $7, 'PENDING', null, null, $8, $8)
-        on conflict do nothing
-        "#,
+    enqueue_bot_action(
+        &state.pool,
+        &state.config,
+        ActionType::ClosePrWithComment,
+        Some(challenge_id),
+        installation_id,
+        github_repo_id,
+        repo_full_name,
+        github_pr_number,
+        payload,
     )
-    .bind(Uuid::new_v4())
-    .bind(challenge_id)
-    .bind(installation_id)
-    .bind(github_repo_id)
-    .bind(repo_full_name)
-    .bind(github_pr_number)
-    .bind(payload)
-    .bind(Utc::now())
-    .execute(&state.pool)
     .await?;
     Ok(())
 }
@@ -163,13 +279,24 @@ async fn cleanup_retention(state: &AppState) -> ApiResult<()> {
         .await?
         .rows_affected();
 
-    let deleted_audits = sqlx::query("delete from audit_events where created_at < $1")
-        .bind(cutoff)
+    let (deleted_audits, anonymized_audits) = match state.config.audit_retention_mode {
+        AuditRetentionMode::Delete => {
+            let deleted = sqlx::query("delete from audit_events where created_at < $1")
+                .bind(cutoff)
+                .execute(&state.pool)
+                .await?
+                .rows_affected();
+            (deleted, 0)
+        }
+        AuditRetentionMode::Anonymize => (0, anonymize_audit_events(state, cutoff).await?),
+    };
+    let deleted_replays = sqlx::query("delete from internal_request_replays where created_at < $1")
+        .bind(Utc::now() - chrono::Duration::days(2))
         .execute(&state.pool)
         .await?
         .rows_affected();
-    let deleted_replays = sqlx::query("delete from internal_request_replays where created_at < $1")
-        .bind(Utc::now() - chrono::Duration::days(2))
+    let deleted_raw_events = sqlx::query("delete from raw_events where received_at < $1")
+        .bind(Utc::now() - chrono::Duration::days(30))
         .execute(&state.pool)
         .await?
         .rows_affected();
@@ -177,7 +304,9 @@ async fn cleanup_retention(state: &AppState) -> ApiResult<()> {
     tracing::info!(
         deleted_confirmations,
         deleted_audits,
+        anonymized_audits,
         deleted_replays,
+        deleted_raw_events,
         cutoff = %cutoff,
         "retention cleanup completed"
     );
@@ -185,6 +314,90 @@ async fn cleanup_retention(state: &AppState) -> ApiResult<()> {
     Ok(())
 }
 
+/// Payload keys holding logins, wallet addresses or ENS names; their values are replaced by keyed
+/// hashes.
+const AUDIT_PII_KEYS: &[&str] = &[
+    "github_login",
+    "login",
+    "wallet_address",
+    "cold_wallet_address",
+    "recovery_wallet_address",
+    "ens_name",
+    "signer",
+];
+const AUDIT_ANONYMIZE_BATCH: i64 = 500;
+
+async fn anonymize_audit_events(state: &AppState, cutoff: chrono::DateTime<Utc>) -> ApiResult<u64> {
+    let key = state
+        .config
+        .audit_anonymize_key
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("AUDIT_ANONYMIZE_KEY is not set"))?;
+    let mut anonymized = 0;
+    loop {
+        let rows: Vec<(Uuid, Value)> = sqlx::query_as(
+            r#"
+            select id, payload from audit_events
+            where created_at < $1 and not (payload ? 'pii_redacted')
+            order by created_at
+            limit $2
+            "#,
+        )
+        .bind(cutoff)
+        .bind(AUDIT_ANONYMIZE_BATCH)
+        .fetch_all(&state.pool)
+        .await?;
+        if rows.is_empty() {
+            return Ok(anonymized);
+        }
+
+        for (id, mut payload) in rows {
+            redact_audit_payload(&mut payload, key.as_bytes());
+            sqlx::query("update audit_events set payload = $2 where id = $1")
+                .bind(id)
+                .bind(payload)
+                .execute(&state.pool)
+                .await?;
+            anonymized += 1;
+        }
+    }
+}
+
+/// Replaces PII values in place with an HMAC under `key` (so equal values still group together,
+/// but cannot be recovered by hashing guesses without the key) and marks the payload so later
+/// runs skip it. Non-object payloads are wrapped so they can carry the marker.
+fn redact_audit_payload(payload: &mut Value, key: &[u8]) {
+    fn redact(value: &mut Value, key: &[u8]) {
+        match value {
+            Value::Object(map) => {
+                for (name, field) in map.iter_mut() {
+                    if AUDIT_PII_KEYS.contains(&name.as_str()) {
+                        if let Some(raw) = field.as_str() {
+                            let mut mac = Hmac::<Sha256>::new_from_slice(key)
+                                .expect("HMAC accepts keys of any length");
+                            mac.update(raw.to_lowercase().as_bytes());
+                            let digest = mac.finalize().into_bytes();
+                            *field = Value::String(format!("hmac-sha256:{}", hex::encode(digest)));
+                        }
+                    } else {
+                        redact(field, key);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| redact(item, key)),
+            _ => {}
+        }
+    }
+
+    redact(payload, key);
+    if !payload.is_object() {
+        *payload = json!({ "value": payload.take() });
+    }
+    if let Value::Object(map) = payload {
+        map.insert("pii_redacted".to_string(), Value::Bool(true));
+    }
+}
+
 fn retention_cutoff(now_unix: i64) -> chrono::DateTime<Utc> {
     chrono::DateTime::from_timestamp(now_unix, 0).expect("valid now timestamp")
         - chrono::Duration::days(365)
@@ -200,4 +413,256 @@ mod tests {
         let cutoff = retention_cutoff(now);
         assert_eq!(cutoff.timestamp(), now - 365 * 24 * 60 * 60);
     }
+
+    #[test]
+    fn tick_delays_stay_within_the_jitter_window() {
+        let period = Duration::from_secs(60);
+        assert_eq!(jittered_delay(period, 10, -1.0), Duration::from_secs(54));
+        assert_eq!(jittered_delay(period, 10, 1.0), Duration::from_secs(66));
+        assert_eq!(jittered_delay(period, 0, 1.0), period);
+        for _ in 0..1000 {
+            let next = next_tick_delay(period, 10);
+            assert!((Duration::from_secs(54)..=Duration::from_secs(66)).contains(&next));
+            assert!(startup_delay(period, 10) <= Duration::from_secs(6));
+        }
+        assert_eq!(startup_delay(period, 0), Duration::ZERO);
+    }
+
+    #[test]
+    fn redacts_pii_and_marks_payload() {
+        let mut payload = json!({
+            "github_user_id": 1001,
+            "github_login": "Alice",
+            "ens_name": "alice.eth",
+            "nested": {"wallet_address": "0xABC", "recovery_wallet_address": "0xDEF"},
+        });
+        redact_audit_payload(&mut payload, b"audit-key");
+
+        assert_eq!(payload["github_user_id"], 1001);
+        assert_eq!(payload["pii_redacted"], true);
+        let login = payload["github_login"].as_str().expect("hashed login");
+        assert!(login.starts_with("hmac-sha256:") && !login.contains("lice"));
+        // An unkeyed digest could be reversed by hashing candidate logins.
+        let plain_digest = hex::encode(<Sha256 as sha2::Digest>::digest(b"alice"));
+        assert!(!login.contains(&plain_digest));
+        let mut same_login = json!({"github_login": "alice"});
+        redact_audit_payload(&mut same_login, b"audit-key");
+        assert_eq!(same_login["github_login"], payload["github_login"]);
+        let mut other_key = json!({"github_login": "alice"});
+        redact_audit_payload(&mut other_key, b"other-key");
+        assert_ne!(other_key["github_login"], payload["github_login"]);
+        assert_ne!(payload["nested"]["wallet_address"], "0xABC");
+        assert_ne!(payload["nested"]["recovery_wallet_address"], "0xDEF");
+        assert_ne!(payload["ens_name"], "alice.eth");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL postgres"]
+    async fn anonymize_mode_keeps_old_audit_rows_without_pii() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let pool = sqlx::PgPool::connect(&url).await.expect("connect");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("apply migrations");
+        let state = AppState::new(
+            pool,
+            crate::config::Config {
+                audit_retention_mode: AuditRetentionMode::Anonymize,
+                audit_anonymize_key: Some("audit-key".to_string()),
+                ..crate::config::Config::for_tests()
+            },
+        );
+        let entity_id = Uuid::new_v4().to_string();
+        for age_days in [400, 500, 1] {
+            sqlx::query(
+                "insert into audit_events (id, event_type, entity_type, entity_id, payload, created_at) values ($1, 'WALLET_LINKED', 'user', $2, $3, $4)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(&entity_id)
+            .bind(json!({"wallet_address": "0xabc", "github_login": "alice"}))
+            .bind(Utc::now() - chrono::Duration::days(age_days))
+            .execute(&state.pool)
+            .await
+            .expect("insert audit");
+        }
+
+        cleanup_retention(&state).await.expect("retention");
+
+        let rows: Vec<(Value, bool)> = sqlx::query_as(
+            "select payload, created_at < now() - interval '365 days' from audit_events where entity_id = $1",
+        )
+        .bind(&entity_id)
+        .fetch_all(&state.pool)
+        .await
+        .expect("audit rows");
+        assert_eq!(rows.len(), 3);
+        for (payload, old) in rows {
+            if old {
+                assert_eq!(payload["pii_redacted"], true);
+                assert_ne!(payload["wallet_address"], "0xabc");
+                assert_ne!(payload["github_login"], "alice");
+            } else {
+                assert_eq!(payload["wallet_address"], "0xabc");
+            }
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL postgres"]
+    async fn timed_out_challenge_enqueues_closure_comment() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let pool = sqlx::PgPool::connect(&url).await.expect("connect");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("apply migrations");
+        let state = AppState::new(
+            pool,
+            Config {
+                timeout_close_comment: Some("Closed for missing stake, see the docs.".to_string()),
+                ..Config::for_tests()
+            },
+        );
+
+        let repo_id = rand::random::<u32>() as i64 + 1;
+        let installation_id = repo_id;
+        sqlx::raw_sql(&format!(
+            r#"
+            insert into github_installations (installation_id, account_login, account_type, active, created_at, updated_at)
+              values ({installation_id}, 'org', 'Organization', true, now(), now());
+            insert into github_installation_repositories (installation_id, github_repo_id, full_name, active, created_at, updated_at)
+              values ({installation_id}, {repo_id}, 'org/repo', true, now(), now());
+            insert into repo_configs (
+              github_repo_id, installation_id, full_name, draft_prs_gated, threshold_wei, input_mode, input_value,
+              spot_price_usd, spot_source, spot_at, spot_quote_id, spot_from_cache, created_at, updated_at
+            )
+            values ({repo_id}, {installation_id}, 'org/repo', true, 1000, 'ETH', 0.000000000000001, 2000, 'coingecko', now(), null, false, now(), now());
+            insert into repo_whitelist (id, github_repo_id, github_user_id, github_login, created_at)
+              values (gen_random_uuid(), {repo_id}, 2002, 'bob', now());
+            "#
+        ))
+        .execute(&state.pool)
+        .await
+        .expect("seed repo");
+
+        let mut challenges = Vec::new();
+        for (pr_number, author_id) in [(1, 1001i64), (2, 2002)] {
+            let challenge_id = Uuid::new_v4();
+            sqlx::query(
+                r#"
+                insert into pr_challenges (
+                  id, gate_token, github_repo_id, github_repo_full_name, github_pr_number,
+                  github_pr_author_id, github_pr_author_login, head_sha, threshold_wei_snapshot,
+                  draft_at_creation, deadline_at, status, verified_wallet_address, created_at, updated_at
+                )
+                values ($1, $2, $3, 'org/repo', $4, $5, 'author', 'sha', 1000, false,
+                        now() - interval '1 minute', 'PENDING', null, now(), now())
+                "#,
+            )
+            .bind(challenge_id)
+            .bind(challenge_id.to_string())
+            .bind(repo_id)
+            .bind(pr_number)
+            .bind(author_id)
+            .execute(&state.pool)
+            .await
+            .expect("seed challenge");
+            challenges.push(challenge_id);
+        }
+
+        process_due_challenges(&state).await.expect("sweep");
+
+        let actions: Vec<(Uuid, String, Value)> = sqlx::query_as(
+            "select challenge_id, action_type, payload from bot_actions where github_repo_id = $1",
+        )
+        .bind(repo_id)
+        .fetch_all(&state.pool)
+        .await
+        .expect("bot actions");
+        assert_eq!(
+            actions.len(),
+            1,
+            "the whitelisted author's PR is not closed"
+        );
+        let (challenge_id, action_type, payload) = &actions[0];
+        assert_eq!(*challenge_id, challenges[0]);
+        assert_eq!(action_type, "CLOSE_PR_WITH_COMMENT");
+        assert_eq!(
+            payload["comment_markdown"],
+            "Closed for missing stake, see the docs."
+        );
+        assert_eq!(
+            payload["comment_marker"],
+            format!("sitg:timeout:{}", challenges[0])
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL postgres"]
+    async fn deadline_sweeper_skips_archived_repos() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let pool = sqlx::PgPool::connect(&url).await.expect("connect");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("apply migrations");
+        let state = AppState::new(pool, Config::for_tests());
+
+        let repo_id = rand::random::<u32>() as i64 + 1;
+        let installation_id = repo_id;
+        let challenge_id = Uuid::new_v4();
+        sqlx::raw_sql(&format!(
+            r#"
+            insert into github_installations (installation_id, account_login, account_type, active, created_at, updated_at)
+              values ({installation_id}, 'org', 'Organization', true, now(), now());
+            insert into github_installation_repositories (installation_id, github_repo_id, full_name, active, created_at, updated_at)
+              values ({installation_id}, {repo_id}, 'org/repo', true, now(), now());
+            insert into repo_configs (
+              github_repo_id, installation_id, full_name, draft_prs_gated, threshold_wei, input_mode, input_value,
+              spot_price_usd, spot_source, spot_at, spot_quote_id, spot_from_cache, created_at, updated_at, archived_at
+            )
+            values ({repo_id}, {installation_id}, 'org/repo', true, 1000, 'ETH', 0.000000000000001, 2000, 'coingecko', now(), null, false, now(), now(), now());
+            insert into pr_challenges (
+              id, gate_token, github_repo_id, github_repo_full_name, github_pr_number,
+              github_pr_author_id, github_pr_author_login, head_sha, threshold_wei_snapshot,
+              draft_at_creation, deadline_at, status, verified_wallet_address, created_at, updated_at
+            )
+            values ('{challenge_id}', '{challenge_id}', {repo_id}, 'org/repo', 1, 1001, 'author', 'sha', 1000, false,
+                    now() - interval '1 minute', 'PENDING', null, now(), now());
+            "#
+        ))
+        .execute(&state.pool)
+        .await
+        .expect("seed archived repo");
+
+        process_due_challenges(&state).await.expect("sweep");
+
+        let status: String = sqlx::query_scalar("select status from pr_challenges where id = $1")
+            .bind(challenge_id)
+            .fetch_one(&state.pool)
+            .await
+            .expect("challenge status");
+        assert_eq!(status, "PENDING");
+        let actions: i64 =
+            sqlx::query_scalar("select count(*) from bot_actions where github_repo_id = $1")
+                .bind(repo_id)
+                .fetch_one(&state.pool)
+                .await
+                .expect("bot actions");
+        assert_eq!(actions, 0);
+    }
+
+    #[test]
+    fn default_timeout_close_comment_links_the_app() {
+        let comment = timeout_close_comment(&Config::for_tests());
+        assert!(comment.contains("has been closed"));
+        assert!(comment.contains("https://sitg.io and reopen"));
+    }
 }
@@ -0,0 +1,135 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use sqlx::{Connection, PgConnection, PgPool};
+
+/// `pg_try_advisory_lock` key guarding the deadline sweeper and retention cleanup.
+pub const JOBS_LEADER_LOCK_KEY: i64 = 0x7369_7467_6a6f_6273; // "sitgjobs"
+
+/// A session-level advisory lock held on a connection taken out of the pool. Postgres releases
+/// the lock when that connection goes away, so a crashed leader cannot keep it.
+pub struct LeaderLock {
+    conn: PgConnection,
+}
+
+impl LeaderLock {
+    /// Returns `None` when another session already holds `key`.
+    pub async fn try_acquire(pool: &PgPool, key: i64) -> Result<Option<Self>, sqlx::Error> {
+        let mut conn = pool.acquire().await?;
+        let acquired: bool = sqlx::query_scalar("select pg_try_advisory_lock($1)")
+            .bind(key)
+            .fetch_one(&mut *conn)
+            .await?;
+        if !acquired {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            conn: conn.detach(),
+        }))
+    }
+
+    /// Whether the session holding the lock is still alive.
+    pub async fn is_held(&mut self) -> bool {
+        self.conn.ping().await.is_ok()
+    }
+
+    /// Ending the session gives the lock up.
+    #[cfg(test)]
+    pub async fn release(self) -> Result<(), sqlx::Error> {
+        self.conn.close().await
+    }
+}
+
+/// Keeps trying to take `key` every `interval` and mirrors the outcome into `is_leader`; once
+/// held, checks the lock's session on the same cadence and steps down if it is lost.
+pub async fn run_leader_election(
+    pool: PgPool,
+    key: i64,
+    interval: Duration,
+    is_leader: Arc<AtomicBool>,
+) {
+    let mut lock: Option<LeaderLock> = None;
+    loop {
+        lock = match lock.take() {
+            Some(mut held) => {
+                if held.is_held().await {
+                    Some(held)
+                } else {
+                    tracing::warn!("lost background job leadership");
+                    None
+                }
+            }
+            None => match LeaderLock::try_acquire(&pool, key).await {
+                Ok(Some(acquired)) => {
+                    tracing::info!("acquired background job leadership");
+                    Some(acquired)
+                }
+                Ok(None) => None,
+                Err(err) => {
+                    tracing::error!(error = %err, "leader election attempt failed");
+                    None
+                }
+            },
+        };
+        is_leader.store(lock.is_some(), Ordering::Relaxed);
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL postgres"]
+    async fn only_one_pool_acquires_the_leader_lock() {
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let first = PgPool::connect(&url).await.expect("connect first");
+        let second = PgPool::connect(&url).await.expect("connect second");
+        let key = rand::random::<i64>();
+
+        let leader = LeaderLock::try_acquire(&first, key)
+            .await
+            .expect("first attempt")
+            .expect("first pool leads");
+        assert!(
+            LeaderLock::try_acquire(&second, key)
+                .await
+                .expect("second attempt")
+                .is_none(),
+            "second pool stands by while the lock is held"
+        );
+        assert!(
+            LeaderLock::try_acquire(&first, key)
+                .await
+                .expect("repeat attempt")
+                .is_none(),
+            "other sessions of the leader's pool stand by too"
+        );
+
+        leader.release().await.expect("release");
+        // The server drops the lock when the closed session's backend exits, shortly after.
+        let mut successor = None;
+        for _ in 0..40 {
+            successor = LeaderLock::try_acquire(&second, key)
+                .await
+                .expect("takeover attempt");
+            if successor.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(25)).await;
+        }
+        successor
+            .expect("second pool takes over")
+            .release()
+            .await
+            .expect("release successor");
+    }
+}
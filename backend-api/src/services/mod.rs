@@ -1,6 +1,12 @@
+pub mod bot_actions;
 pub mod github_oauth;
+pub mod http_client;
+pub mod identity_service;
+pub mod installations;
 pub mod internal_auth;
 pub mod jobs;
+pub mod leader;
+pub mod outbound_webhooks;
 pub mod quote_service;
 pub mod rate_limiter;
 pub mod signature_service;
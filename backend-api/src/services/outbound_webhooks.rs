@@ -0,0 +1,336 @@
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    config::Config,
+    error::{ApiError, ApiResult},
+};
+
+/// Deliveries are retried with backoff and given up after this many attempts.
+const OUTBOUND_MAX_ATTEMPTS: i32 = 8;
+const OUTBOUND_BATCH_SIZE: i64 = 50;
+/// A claimed event is not picked up again by another replica until this lease runs out.
+const OUTBOUND_CLAIM_LEASE_MINUTES: i64 = 5;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Body sent to subscribers for `WALLET_LINKED` / `WALLET_UNLINKED`.
+pub fn wallet_event_payload(
+    event_id: Uuid,
+    event_type: &str,
+    occurred_at: DateTime<Utc>,
+    github_user_id: i64,
+    github_login: &str,
+    wallet_address: &str,
+) -> Value {
+    json!({
+      "id": event_id,
+      "event_type": event_type,
+      "occurred_at": occurred_at,
+      "data": {
+        "github_user_id": github_user_id,
+        "github_login": github_login,
+        "wallet_address": wallet_address,
+      }
+    })
+}
+
+/// `x-sitg-signature` value: HMAC-SHA256 over `{timestamp}.{body}`, the same scheme bots use to
+/// sign internal requests.
+pub fn sign_outbound_body(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Queues a wallet event when a webhook URL is configured and the event is subscribed to.
+/// Returns whether an event was queued.
+pub async fn enqueue_wallet_event(
+    executor: impl sqlx::PgExecutor<'_>,
+    config: &Config,
+    event_type: &str,
+    github_user_id: i64,
+    github_login: &str,
+    wallet_address: &str,
+) -> ApiResult<bool> {
+    if config.outbound_webhook_url.is_none()
+        || !config
+            .outbound_webhook_events
+            .iter()
+            .any(|e| e == event_type)
+    {
+        return Ok(false);
+    }
+
+    let event_id = Uuid::new_v4();
+    let now = Utc::now();
+    sqlx::query(
+        r#"
+        insert into outbound_events (id, event_type, payload, status, attempts, next_attempt_at, created_at)
+        values ($1, $2, $3, 'PENDING', 0, $4, $4)
+        "#,
+    )
+    .bind(event_id)
+    .bind(event_type)
+    .bind(wallet_event_payload(
+        event_id,
+        event_type,
+        now,
+        github_user_id,
+        github_login,
+        wallet_address,
+    ))
+    .bind(now)
+    .execute(executor)
+    .await?;
+    Ok(true)
+}
+
+/// Posts due events to `OUTBOUND_WEBHOOK_URL`. Returns how many were delivered.
+pub async fn deliver_due_events(state: &AppState, client: &reqwest::Client) -> ApiResult<usize> {
+    let (Some(url), Some(secret)) = (
+        state.config.outbound_webhook_url.as_deref(),
+        state.config.outbound_webhook_secret.as_deref(),
+    ) else {
+        return Ok(0);
+    };
+
+    let now = Utc::now();
+    let due: Vec<(Uuid, String, Value, i32)> = sqlx::query_as(
+        r#"
+        update outbound_events
+        set attempts = attempts + 1, next_attempt_at = $2
+        where id in (
+          select id from outbound_events
+          where status = 'PENDING' and next_attempt_at <= $1
+          order by next_attempt_at
+          limit $3
+          for update skip locked
+        )
+        returning id, event_type, payload, attempts
+        "#,
+    )
+    .bind(now)
+    .bind(now + Duration::minutes(OUTBOUND_CLAIM_LEASE_MINUTES))
+    .bind(OUTBOUND_BATCH_SIZE)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut delivered = 0;
+    for (id, event_type, payload, attempts) in due {
+        match post_event(client, url, secret, id, &event_type, &payload).await {
+            Ok(()) => {
+                sqlx::query(
+                    "update outbound_events set status = 'DELIVERED', delivered_at = $2, last_error = null where id = $1",
+                )
+                .bind(id)
+                .bind(Utc::now())
+                .execute(&state.pool)
+                .await?;
+                delivered += 1;
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, event_id = %id, event_type, attempts, "outbound webhook delivery failed");
+                let status = if attempts >= OUTBOUND_MAX_ATTEMPTS {
+                    "FAILED"
+                } else {
+                    "PENDING"
+                };
+                sqlx::query(
+                    "update outbound_events set status = $2, next_attempt_at = $3, last_error = $4 where id = $1",
+                )
+                .bind(id)
+                .bind(status)
+                .bind(Utc::now() + retry_backoff(attempts))
+                .bind(err.to_string())
+                .execute(&state.pool)
+                .await?;
+            }
+        }
+    }
+    Ok(delivered)
+}
+
+async fn post_event(
+    client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    id: Uuid,
+    event_type: &str,
+    payload: &Value,
+) -> ApiResult<()> {
+    let body = serde_json::to_vec(payload).map_err(|e| ApiError::Internal(e.into()))?;
+    let timestamp = Utc::now().timestamp();
+    let response = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header("x-sitg-event", event_type)
+        .header("x-sitg-delivery", id.to_string())
+        .header("x-sitg-timestamp", timestamp.to_string())
+        .header(
+            "x-sitg-signature",
+            sign_outbound_body(secret, timestamp, &body),
+        )
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| ApiError::Internal(e.into()))?;
+    if !response.status().is_success() {
+        return Err(ApiError::Internal(anyhow::anyhow!(
+            "subscriber answered {}",
+            response.status()
+        )));
+    }
+    Ok(())
+}
+
+/// 1, 2, 4, ... minutes, capped at an hour.
+fn retry_backoff(attempts: i32) -> Duration {
+    Duration::minutes((1i64 << attempts.clamp(1, 7).saturating_sub(1)).min(60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_wallet_event_payload() {
+        let id = Uuid::nil();
+        let at = DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .expect("timestamp")
+            .with_timezone(&Utc);
+        let payload = wallet_event_payload(id, "WALLET_LINKED", at, 1001, "alice", "0xabc");
+        assert_eq!(
+            payload,
+            json!({
+              "id": "00000000-0000-0000-0000-000000000000",
+              "event_type": "WALLET_LINKED",
+              "occurred_at": "2026-03-01T12:00:00Z",
+              "data": {"github_user_id": 1001, "github_login": "alice", "wallet_address": "0xabc"}
+            })
+        );
+    }
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        let signature = sign_outbound_body("secret", 1_700_000_000, b"{}");
+        let mut mac = HmacSha256::new_from_slice(b"secret").expect("key");
+        mac.update(b"1700000000.{}");
+        mac.verify_slice(&hex::decode(signature.trim_start_matches("sha256=")).expect("hex"))
+            .expect("signature verifies");
+        assert_ne!(
+            signature,
+            sign_outbound_body("secret", 1_700_000_001, b"{}")
+        );
+    }
+
+    #[test]
+    fn backs_off_exponentially_up_to_an_hour() {
+        assert_eq!(retry_backoff(1), Duration::minutes(1));
+        assert_eq!(retry_backoff(3), Duration::minutes(4));
+        assert_eq!(retry_backoff(7), Duration::minutes(60));
+        assert_eq!(retry_backoff(20), Duration::minutes(60));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires DATABASE_URL postgres"]
+    async fn enqueues_subscribed_events_and_delivers_them_signed() {
+        use std::sync::{Arc, Mutex};
+
+        use axum::{Router, http::HeaderMap, routing::post};
+
+        let Ok(url) = std::env::var("DATABASE_URL") else {
+            return;
+        };
+        let pool = sqlx::PgPool::connect(&url).await.expect("connect");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("apply migrations");
+
+        let received: Arc<Mutex<Vec<(HeaderMap, String)>>> = Arc::default();
+        let captured = received.clone();
+        let app = Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: String| async move {
+                captured.lock().expect("hook lock").push((headers, body));
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind hook stub");
+        let addr = listener.local_addr().expect("hook stub addr");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve hook stub");
+        });
+
+        let state = AppState::new(
+            pool,
+            Config {
+                outbound_webhook_url: Some(format!("http://{addr}/hook")),
+                outbound_webhook_secret: Some("hook-secret".to_string()),
+                outbound_webhook_events: vec!["WALLET_LINKED".to_string()],
+                ..Config::for_tests()
+            },
+        );
+        let wallet = format!("0x{}", Uuid::new_v4().simple());
+
+        let queued = enqueue_wallet_event(
+            &state.pool,
+            &state.config,
+            "WALLET_LINKED",
+            1001,
+            "alice",
+            &wallet,
+        )
+        .await
+        .expect("enqueue linked");
+        assert!(queued);
+        let queued = enqueue_wallet_event(
+            &state.pool,
+            &state.config,
+            "WALLET_UNLINKED",
+            1001,
+            "alice",
+            &wallet,
+        )
+        .await
+        .expect("enqueue unlinked");
+        assert!(!queued, "unsubscribed events are not queued");
+
+        deliver_due_events(&state, &reqwest::Client::new())
+            .await
+            .expect("deliver");
+
+        let status: String = sqlx::query_scalar(
+            "select status from outbound_events where payload->'data'->>'wallet_address' = $1",
+        )
+        .bind(&wallet)
+        .fetch_one(&state.pool)
+        .await
+        .expect("event row");
+        assert_eq!(status, "DELIVERED");
+
+        let received = received.lock().expect("hook lock");
+        let (headers, body) = received
+            .iter()
+            .find(|(_, body)| body.contains(&wallet))
+            .expect("delivery received");
+        assert_eq!(headers["x-sitg-event"], "WALLET_LINKED");
+        let timestamp: i64 = headers["x-sitg-timestamp"]
+            .to_str()
+            .expect("timestamp")
+            .parse()
+            .expect("numeric timestamp");
+        assert_eq!(
+            headers["x-sitg-signature"],
+            sign_outbound_body("hook-secret", timestamp, body.as_bytes())
+        );
+    }
+}
@@ -1,22 +1,116 @@
-use std::time::Duration as StdDuration;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration as StdDuration, Instant},
+};
 
 use chrono::{Duration, Utc};
 use rust_decimal::Decimal;
 use serde::Deserialize;
 use sqlx::PgPool;
+use tokio::task::JoinSet;
 use uuid::Uuid;
 
 use crate::{
+    config::{Config, QuoteStrategy},
     error::{ApiError, ApiResult},
     models::db::SpotQuoteRow,
+    services::http_client::outbound_client_builder,
 };
 
+const PRICE_FETCH_MAX_ATTEMPTS: u32 = 3;
+const PRICE_FETCH_INITIAL_BACKOFF: StdDuration = StdDuration::from_millis(200);
+/// Fewer agreeing sources than this and a median cannot outvote a single bad feed.
+const MEDIAN_MIN_SOURCES: usize = 2;
+
 #[derive(Clone)]
 pub struct QuoteService {
     pool: PgPool,
     client: reqwest::Client,
     coingecko_base_url: String,
     coinbase_base_url: String,
+    kraken_base_url: String,
+    strategy: QuoteStrategy,
+    /// How long past its `expires_at` a cached quote may still be served while refreshing.
+    stale_while_revalidate: Duration,
+    /// Lifetime given to freshly fetched quotes.
+    cache_ttl: Duration,
+    /// Cached quotes fetched longer ago than this are never served; `None` serves any age.
+    max_staleness: Option<Duration>,
+    refresh_in_flight: Arc<AtomicBool>,
+    breakers: Arc<Mutex<HashMap<&'static str, BreakerState>>>,
+    /// Consecutive failures that open a source's breaker; `0` disables the breaker.
+    breaker_failure_threshold: u32,
+    breaker_cooldown: StdDuration,
+}
+
+/// Circuit breaker for one price source. While `open_until` is in the future the source is
+/// skipped; the first call after it passes is let through as a probe and re-arms the cooldown,
+/// so a probe that never reports back (e.g. aborted by the fastest strategy) cannot wedge it.
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// What to do with the newest cached quote before (or instead of) a live fetch.
+#[derive(Debug, PartialEq, Eq)]
+enum CachedQuoteUse {
+    Serve,
+    ServeAndRefresh,
+    FetchLive,
+}
+
+fn cached_quote_use(
+    now: chrono::DateTime<Utc>,
+    cached_expires_at: Option<chrono::DateTime<Utc>>,
+    stale_while_revalidate: Duration,
+) -> CachedQuoteUse {
+    if stale_while_revalidate <= Duration::zero() {
+        return CachedQuoteUse::FetchLive;
+    }
+    match cached_expires_at {
+        Some(expires_at) if now < expires_at => CachedQuoteUse::Serve,
+        Some(expires_at) if now < expires_at + stale_while_revalidate => {
+            CachedQuoteUse::ServeAndRefresh
+        }
+        _ => CachedQuoteUse::FetchLive,
+    }
+}
+
+/// Fiat currency an ETH price is quoted in; quotes are cached per `ETH_{CURRENCY}` pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FiatCurrency {
+    Usd,
+    Eur,
+    Gbp,
+}
+
+impl FiatCurrency {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_uppercase().as_str() {
+            "USD" => Some(Self::Usd),
+            "EUR" => Some(Self::Eur),
+            "GBP" => Some(Self::Gbp),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Usd => "USD",
+            Self::Eur => "EUR",
+            Self::Gbp => "GBP",
+        }
+    }
+
+    fn pair(self) -> String {
+        format!("ETH_{}", self.as_str())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -26,16 +120,14 @@ pub struct QuoteSelection {
     pub price: Decimal,
     pub fetched_at: chrono::DateTime<Utc>,
     pub from_cache: bool,
+    /// Served from cache past its `expires_at`, though within the staleness cutoff.
+    pub stale: bool,
 }
 
+/// `ethereum` maps each requested `vs_currencies` entry (lowercase) to its price.
 #[derive(Debug, Deserialize)]
 struct CoinGeckoPriceEnvelope {
-    ethereum: CoinGeckoPrice,
-}
-
-#[derive(Debug, Deserialize)]
-struct CoinGeckoPrice {
-    usd: Decimal,
+    ethereum: HashMap<String, Decimal>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -48,9 +140,23 @@ struct CoinbaseSpotData {
     amount: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct KrakenTickerEnvelope {
+    #[serde(default)]
+    error: Vec<String>,
+    #[serde(default)]
+    result: HashMap<String, KrakenTicker>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrakenTicker {
+    /// Last trade as `[price, lot volume]`.
+    c: Vec<String>,
+}
+
 impl QuoteService {
-    pub fn new(pool: PgPool) -> Self {
-        let client = reqwest::Client::builder()
+    pub fn new(pool: PgPool, config: &Config) -> Self {
+        let client = outbound_client_builder(config)
             .user_agent("sitg-backend")
             .timeout(StdDuration::from_secs(8))
             .build()
@@ -61,11 +167,25 @@ impl QuoteService {
             client,
             coingecko_base_url: "https://api.coingecko.com".to_string(),
             coinbase_base_url: "https://api.coinbase.com".to_string(),
+            kraken_base_url: "https://api.kraken.com".to_string(),
+            strategy: config.quote_strategy,
+            stale_while_revalidate: Duration::seconds(config.quote_stale_while_revalidate_seconds),
+            cache_ttl: Duration::seconds(config.quote_cache_ttl_secs),
+            max_staleness: (config.quote_max_staleness_secs > 0)
+                .then(|| Duration::seconds(config.quote_max_staleness_secs)),
+            refresh_in_flight: Arc::default(),
+            breakers: Arc::default(),
+            breaker_failure_threshold: config.quote_breaker_failure_threshold,
+            breaker_cooldown: StdDuration::from_secs(config.quote_breaker_cooldown_secs),
         }
     }
 
     #[cfg(test)]
-    fn with_base_urls(pool: PgPool, coingecko_base_url: String, coinbase_base_url: String) -> Self {
+    pub(crate) fn with_base_urls(
+        pool: PgPool,
+        coingecko_base_url: String,
+        coinbase_base_url: String,
+    ) -> Self {
         let client = reqwest::Client::builder()
             .user_agent("sitg-backend")
             .timeout(StdDuration::from_secs(8))
@@ -77,62 +197,216 @@ impl QuoteService {
             client,
             coingecko_base_url,
             coinbase_base_url,
+            kraken_base_url: "http://127.0.0.1:9".to_string(),
+            strategy: QuoteStrategy::FirstSuccess,
+            stale_while_revalidate: Duration::zero(),
+            cache_ttl: Duration::minutes(5),
+            max_staleness: None,
+            refresh_in_flight: Arc::default(),
+            breakers: Arc::default(),
+            breaker_failure_threshold: 3,
+            breaker_cooldown: StdDuration::from_secs(30),
         }
     }
 
     pub async fn live_or_cached_eth_usd_quote(&self) -> ApiResult<QuoteSelection> {
-        match self.fetch_live().await {
+        self.live_or_cached_eth_quote(FiatCurrency::Usd).await
+    }
+
+    pub async fn live_or_cached_eth_quote(
+        &self,
+        currency: FiatCurrency,
+    ) -> ApiResult<QuoteSelection> {
+        if self.stale_while_revalidate > Duration::zero() {
+            let now = Utc::now();
+            let cached = self.fetch_latest_cached_row(currency).await?;
+            let expires_at = cached.as_ref().map(|row| row.expires_at);
+            let decision = cached_quote_use(now, expires_at, self.stale_while_revalidate);
+            // A quote past the staleness cutoff falls through to a live fetch instead.
+            if let Ok(selection) = cached_selection(cached, now, self.max_staleness) {
+                match decision {
+                    CachedQuoteUse::Serve => return Ok(selection),
+                    CachedQuoteUse::ServeAndRefresh => {
+                        self.spawn_refresh(currency);
+                        return Ok(selection);
+                    }
+                    CachedQuoteUse::FetchLive => {}
+                }
+            }
+        }
+
+        match self.fetch_live(currency).await {
             Ok(live) => Ok(live),
             Err(err) => {
                 tracing::warn!(error = %err, "live quote fetch failed, falling back to cached quote");
-                self.fetch_latest_cached().await
+                self.fetch_latest_cached(currency).await
             }
         }
     }
 
-    async fn fetch_live(&self) -> ApiResult<QuoteSelection> {
-        match self.fetch_live_from_coingecko().await {
+    /// At most one background refresh runs at a time; extra stale hits just serve the cache.
+    fn spawn_refresh(&self, currency: FiatCurrency) {
+        if self.refresh_in_flight.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(err) = service.fetch_live(currency).await {
+                tracing::warn!(error = %err, "background quote refresh failed");
+            }
+            service.refresh_in_flight.store(false, Ordering::Release);
+        });
+    }
+
+    async fn fetch_live(&self, currency: FiatCurrency) -> ApiResult<QuoteSelection> {
+        match self.strategy {
+            QuoteStrategy::Median => return self.fetch_live_median(currency).await,
+            QuoteStrategy::Fastest => {
+                let (source, price) = self.fetch_fastest_price(currency).await?;
+                return self.persist_live_quote(currency, source, price).await;
+            }
+            QuoteStrategy::FirstSuccess => {}
+        }
+        match self.fetch_live_from_coingecko(currency).await {
             Ok(quote) => Ok(quote),
             Err(primary_err) => {
                 tracing::warn!(error = %primary_err, "coingecko quote fetch failed, trying coinbase");
-                self.fetch_live_from_coinbase().await
+                match self.fetch_live_from_coinbase(currency).await {
+                    Ok(quote) => Ok(quote),
+                    Err(secondary_err) => {
+                        tracing::warn!(error = %secondary_err, "coinbase quote fetch failed, trying kraken");
+                        self.fetch_live_from_kraken(currency).await
+                    }
+                }
+            }
+        }
+    }
+
+    /// Persists the median of every source that answered, recorded as e.g.
+    /// `median:coingecko,coinbase`.
+    async fn fetch_live_median(&self, currency: FiatCurrency) -> ApiResult<QuoteSelection> {
+        let (source, price) = self.fetch_median_price(currency).await?;
+        self.persist_live_quote(currency, &source, price).await
+    }
+
+    async fn fetch_median_price(&self, currency: FiatCurrency) -> ApiResult<(String, Decimal)> {
+        let (coingecko, coinbase, kraken) = tokio::join!(
+            self.guarded("coingecko", self.fetch_coingecko_price(currency)),
+            self.guarded("coinbase", self.fetch_coinbase_price(currency)),
+            self.guarded("kraken", self.fetch_kraken_price(currency)),
+        );
+        let mut prices = Vec::new();
+        for (source, result) in [
+            ("coingecko", coingecko),
+            ("coinbase", coinbase),
+            ("kraken", kraken),
+        ] {
+            match result {
+                Ok(price) if price > Decimal::ZERO => prices.push((source, price)),
+                Ok(price) => {
+                    tracing::warn!(source, %price, "price source returned a non-positive price")
+                }
+                Err(err) => tracing::warn!(source, error = %err, "price source failed"),
             }
         }
+        median_quote(prices).ok_or(ApiError::PriceUnavailable(None))
     }
 
-    async fn fetch_live_from_coingecko(&self) -> ApiResult<QuoteSelection> {
-        let price = self.fetch_coingecko_price().await?;
+    /// Races every source; the first positive price wins and dropping the set aborts the
+    /// requests still in flight, so one degraded provider no longer adds its timeout.
+    async fn fetch_fastest_price(
+        &self,
+        currency: FiatCurrency,
+    ) -> ApiResult<(&'static str, Decimal)> {
+        let mut fetches = JoinSet::new();
+        let service = self.clone();
+        fetches.spawn(async move {
+            let price = service
+                .guarded("coingecko", service.fetch_coingecko_price(currency))
+                .await;
+            ("coingecko", price)
+        });
+        let service = self.clone();
+        fetches.spawn(async move {
+            let price = service
+                .guarded("coinbase", service.fetch_coinbase_price(currency))
+                .await;
+            ("coinbase", price)
+        });
+        let service = self.clone();
+        fetches.spawn(async move {
+            let price = service
+                .guarded("kraken", service.fetch_kraken_price(currency))
+                .await;
+            ("kraken", price)
+        });
+
+        while let Some(joined) = fetches.join_next().await {
+            match joined {
+                Ok((source, Ok(price))) if price > Decimal::ZERO => return Ok((source, price)),
+                Ok((source, Ok(price))) => {
+                    tracing::warn!(source, %price, "price source returned a non-positive price")
+                }
+                Ok((source, Err(err))) => {
+                    tracing::warn!(source, error = %err, "price source failed")
+                }
+                Err(err) => tracing::warn!(error = %err, "price source task failed"),
+            }
+        }
+        Err(ApiError::PriceUnavailable(None))
+    }
+
+    async fn fetch_live_from_coingecko(&self, currency: FiatCurrency) -> ApiResult<QuoteSelection> {
+        let price = self
+            .guarded("coingecko", self.fetch_coingecko_price(currency))
+            .await?;
+        if price <= Decimal::ZERO {
+            return Err(ApiError::PriceUnavailable(None));
+        }
+
+        self.persist_live_quote(currency, "coingecko", price).await
+    }
+
+    async fn fetch_live_from_coinbase(&self, currency: FiatCurrency) -> ApiResult<QuoteSelection> {
+        let price = self
+            .guarded("coinbase", self.fetch_coinbase_price(currency))
+            .await?;
         if price <= Decimal::ZERO {
-            return Err(ApiError::PriceUnavailable);
+            return Err(ApiError::PriceUnavailable(None));
         }
 
-        self.persist_live_quote("coingecko", price).await
+        self.persist_live_quote(currency, "coinbase", price).await
     }
 
-    async fn fetch_live_from_coinbase(&self) -> ApiResult<QuoteSelection> {
-        let price = self.fetch_coinbase_price().await?;
+    async fn fetch_live_from_kraken(&self, currency: FiatCurrency) -> ApiResult<QuoteSelection> {
+        let price = self
+            .guarded("kraken", self.fetch_kraken_price(currency))
+            .await?;
         if price <= Decimal::ZERO {
-            return Err(ApiError::PriceUnavailable);
+            return Err(ApiError::PriceUnavailable(None));
         }
 
-        self.persist_live_quote("coinbase", price).await
+        self.persist_live_quote(currency, "kraken", price).await
     }
 
-    async fn fetch_coingecko_price(&self) -> ApiResult<Decimal> {
+    async fn fetch_coingecko_price(&self, currency: FiatCurrency) -> ApiResult<Decimal> {
         let response = self
-            .client
-            .get(format!(
-                "{}/api/v3/simple/price",
-                self.coingecko_base_url.trim_end_matches('/')
-            ))
-            .query(&[("ids", "ethereum"), ("vs_currencies", "usd")])
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|e| ApiError::Internal(e.into()))?;
+            .send_with_retry(|| {
+                self.client
+                    .get(format!(
+                        "{}/api/v3/simple/price",
+                        self.coingecko_base_url.trim_end_matches('/')
+                    ))
+                    .query(&[
+                        ("ids", "ethereum"),
+                        ("vs_currencies", &currency.as_str().to_lowercase()),
+                    ])
+                    .header("Accept", "application/json")
+            })
+            .await?;
 
         if !response.status().is_success() {
-            return Err(ApiError::PriceUnavailable);
+            return Err(ApiError::PriceUnavailable(None));
         }
 
         let parsed: CoinGeckoPriceEnvelope = response
@@ -140,47 +414,183 @@ impl QuoteService {
             .await
             .map_err(|e| ApiError::Internal(e.into()))?;
 
-        Ok(parsed.ethereum.usd)
+        parsed
+            .ethereum
+            .get(&currency.as_str().to_lowercase())
+            .copied()
+            .ok_or(ApiError::PriceUnavailable(None))
     }
 
-    async fn fetch_coinbase_price(&self) -> ApiResult<Decimal> {
+    async fn fetch_coinbase_price(&self, currency: FiatCurrency) -> ApiResult<Decimal> {
         let response = self
-            .client
-            .get(format!(
-                "{}/v2/prices/ETH-USD/spot",
-                self.coinbase_base_url.trim_end_matches('/')
-            ))
-            .header("Accept", "application/json")
-            .header("CB-VERSION", "2015-04-08")
-            .send()
+            .send_with_retry(|| {
+                self.client
+                    .get(format!(
+                        "{}/v2/prices/ETH-{}/spot",
+                        self.coinbase_base_url.trim_end_matches('/'),
+                        currency.as_str()
+                    ))
+                    .header("Accept", "application/json")
+                    .header("CB-VERSION", "2015-04-08")
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ApiError::PriceUnavailable(None));
+        }
+
+        let parsed: CoinbaseSpotEnvelope = response
+            .json()
             .await
             .map_err(|e| ApiError::Internal(e.into()))?;
 
+        Decimal::from_str_exact(parsed.data.amount.trim())
+            .map_err(|_| ApiError::PriceUnavailable(None))
+    }
+
+    async fn fetch_kraken_price(&self, currency: FiatCurrency) -> ApiResult<Decimal> {
+        let response = self
+            .send_with_retry(|| {
+                self.client
+                    .get(format!(
+                        "{}/0/public/Ticker",
+                        self.kraken_base_url.trim_end_matches('/')
+                    ))
+                    .query(&[("pair", format!("ETH{}", currency.as_str()))])
+                    .header("Accept", "application/json")
+            })
+            .await?;
+
         if !response.status().is_success() {
-            return Err(ApiError::PriceUnavailable);
+            return Err(ApiError::PriceUnavailable(None));
         }
 
-        let parsed: CoinbaseSpotEnvelope = response
+        let parsed: KrakenTickerEnvelope = response
             .json()
             .await
             .map_err(|e| ApiError::Internal(e.into()))?;
+        if !parsed.error.is_empty() {
+            return Err(ApiError::PriceUnavailable(None));
+        }
+
+        parsed
+            .result
+            .values()
+            .next()
+            .and_then(|ticker| ticker.c.first())
+            .and_then(|price| Decimal::from_str_exact(price.trim()).ok())
+            .ok_or(ApiError::PriceUnavailable(None))
+    }
 
-        Decimal::from_str_exact(parsed.data.amount.trim()).map_err(|_| ApiError::PriceUnavailable)
+    /// Runs `fetch` unless `source`'s breaker is open, in which case the source is skipped as
+    /// unavailable without spending a request. Errors and non-positive prices count as failures.
+    async fn guarded(
+        &self,
+        source: &'static str,
+        fetch: impl Future<Output = ApiResult<Decimal>>,
+    ) -> ApiResult<Decimal> {
+        if !self.breaker_allows(source, Instant::now()) {
+            tracing::debug!(source, "price source circuit open, skipping");
+            return Err(ApiError::PriceUnavailable(None));
+        }
+        let result = fetch.await;
+        let succeeded = matches!(result, Ok(price) if price > Decimal::ZERO);
+        self.record_source_outcome(source, succeeded, Instant::now());
+        result
+    }
+
+    fn breaker_allows(&self, source: &'static str, now: Instant) -> bool {
+        if self.breaker_failure_threshold == 0 {
+            return true;
+        }
+        let mut breakers = self.breakers.lock().expect("breaker lock");
+        let Some(state) = breakers.get_mut(source) else {
+            return true;
+        };
+        match state.open_until {
+            Some(open_until) if now < open_until => false,
+            Some(_) => {
+                tracing::info!(source, "price source circuit half-open, probing");
+                state.open_until = Some(now + self.breaker_cooldown);
+                true
+            }
+            None => true,
+        }
     }
 
-    async fn persist_live_quote(&self, source: &str, price: Decimal) -> ApiResult<QuoteSelection> {
+    fn record_source_outcome(&self, source: &'static str, succeeded: bool, now: Instant) {
+        if self.breaker_failure_threshold == 0 {
+            return;
+        }
+        let mut breakers = self.breakers.lock().expect("breaker lock");
+        if succeeded {
+            if breakers
+                .remove(source)
+                .is_some_and(|state| state.open_until.is_some())
+            {
+                tracing::info!(source, "price source circuit closed");
+            }
+            return;
+        }
+        let state = breakers.entry(source).or_default();
+        state.consecutive_failures += 1;
+        if state.consecutive_failures >= self.breaker_failure_threshold {
+            if state.open_until.is_none() {
+                tracing::warn!(
+                    source,
+                    failures = state.consecutive_failures,
+                    "price source circuit opened"
+                );
+            }
+            state.open_until = Some(now + self.breaker_cooldown);
+        }
+    }
+
+    /// Retries 5xx responses and timeouts with exponential backoff before giving up on a
+    /// source; other failures (including 4xx) are returned on the first attempt.
+    async fn send_with_retry(
+        &self,
+        request: impl Fn() -> reqwest::RequestBuilder,
+    ) -> ApiResult<reqwest::Response> {
+        let mut backoff = PRICE_FETCH_INITIAL_BACKOFF;
+        let mut attempt = 1;
+        loop {
+            let can_retry = attempt < PRICE_FETCH_MAX_ATTEMPTS;
+            match request().send().await {
+                Ok(response) if response.status().is_server_error() && can_retry => {
+                    tracing::warn!(status = %response.status(), attempt, "price source error, retrying");
+                }
+                Ok(response) => return Ok(response),
+                Err(err) if err.is_timeout() && can_retry => {
+                    tracing::warn!(error = %err, attempt, "price source timed out, retrying");
+                }
+                Err(err) => return Err(ApiError::Internal(err.into())),
+            }
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+
+    async fn persist_live_quote(
+        &self,
+        currency: FiatCurrency,
+        source: &str,
+        price: Decimal,
+    ) -> ApiResult<QuoteSelection> {
         let id = Uuid::new_v4();
         let now = Utc::now();
-        let expires_at = now + Duration::minutes(5);
+        let expires_at = now + self.cache_ttl;
 
         sqlx::query(
             r#"
             insert into spot_quotes (id, source, pair, price, fetched_at, expires_at, created_at)
-            values ($1, $2, 'ETH_USD', $3, $4, $5, $4)
+            values ($1, $2, $3, $4, $5, $6, $5)
             "#,
         )
         .bind(id)
         .bind(source)
+        .bind(currency.pair())
         .bind(price)
         .bind(now)
         .bind(expires_at)
@@ -193,33 +603,80 @@ impl QuoteService {
             price,
             fetched_at: now,
             from_cache: false,
+            stale: false,
         })
     }
 
-    async fn fetch_latest_cached(&self) -> ApiResult<QuoteSelection> {
+    async fn fetch_latest_cached(&self, currency: FiatCurrency) -> ApiResult<QuoteSelection> {
+        cached_selection(
+            self.fetch_latest_cached_row(currency).await?,
+            Utc::now(),
+            self.max_staleness,
+        )
+    }
+
+    async fn fetch_latest_cached_row(
+        &self,
+        currency: FiatCurrency,
+    ) -> ApiResult<Option<SpotQuoteRow>> {
         let cached: Option<SpotQuoteRow> = sqlx::query_as(
             r#"
-            select id, source, price, fetched_at
+            select id, source, price, fetched_at, expires_at
             from spot_quotes
-            where pair = 'ETH_USD'
+            where pair = $1
             order by fetched_at desc
             limit 1
             "#,
         )
+        .bind(currency.pair())
         .fetch_optional(&self.pool)
         .await?;
+        Ok(cached)
+    }
+}
 
-        match cached {
-            Some(row) => Ok(QuoteSelection {
-                quote_id: row.id,
-                source: row.source,
-                price: row.price,
-                fetched_at: row.fetched_at,
-                from_cache: true,
-            }),
-            None => Err(ApiError::PriceUnavailable),
-        }
+/// Median of the answering sources (mean of the middle two for an even count), rounded to the
+/// precision `spot_quotes.price` stores; `None` below `MEDIAN_MIN_SOURCES`.
+fn median_quote(mut prices: Vec<(&str, Decimal)>) -> Option<(String, Decimal)> {
+    if prices.len() < MEDIAN_MIN_SOURCES {
+        return None;
+    }
+    let sources = prices
+        .iter()
+        .map(|(source, _)| *source)
+        .collect::<Vec<_>>()
+        .join(",");
+    prices.sort_by_key(|(_, price)| *price);
+    let mid = prices.len() / 2;
+    let median = if prices.len().is_multiple_of(2) {
+        (prices[mid - 1].1 + prices[mid].1) / Decimal::TWO
+    } else {
+        prices[mid].1
+    };
+    Some((format!("median:{sources}"), median.round_dp(8)))
+}
+
+/// Rejects a quote fetched more than `max_staleness` ago: an hours-old price can misstate a USD
+/// threshold badly, so the caller gets `PRICE_UNAVAILABLE` (with the quote's age) instead.
+fn cached_selection(
+    cached: Option<SpotQuoteRow>,
+    now: chrono::DateTime<Utc>,
+    max_staleness: Option<Duration>,
+) -> ApiResult<QuoteSelection> {
+    let Some(row) = cached else {
+        return Err(ApiError::PriceUnavailable(None));
+    };
+    if max_staleness.is_some_and(|max| now - row.fetched_at > max) {
+        return Err(ApiError::PriceUnavailable(Some(row.fetched_at)));
     }
+    Ok(QuoteSelection {
+        quote_id: row.id,
+        source: row.source,
+        price: row.price,
+        fetched_at: row.fetched_at,
+        from_cache: true,
+        stale: now >= row.expires_at,
+    })
 }
 
 #[cfg(test)]
@@ -268,7 +725,10 @@ mod tests {
             "http://127.0.0.1:9".to_string(),
         );
 
-        let price = service.fetch_coingecko_price().await.expect("price");
+        let price = service
+            .fetch_coingecko_price(FiatCurrency::Usd)
+            .await
+            .expect("price");
         assert_eq!(price, Decimal::from_str_exact("2010.50").expect("decimal"));
         assert_eq!(
             hits.lock().expect("lock").as_slice(),
@@ -303,7 +763,10 @@ mod tests {
             format!("http://{}", addr),
         );
 
-        let price = service.fetch_coinbase_price().await.expect("price");
+        let price = service
+            .fetch_coinbase_price(FiatCurrency::Usd)
+            .await
+            .expect("price");
         assert_eq!(price, Decimal::from_str_exact("2022.33").expect("decimal"));
         assert_eq!(
             hits.lock().expect("lock").as_slice(),
@@ -311,12 +774,380 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn requests_and_parses_eur_quotes() {
+        let hits = Arc::new(Mutex::new(Vec::<String>::new()));
+        let coingecko_hits = Arc::clone(&hits);
+        let coinbase_hits = Arc::clone(&hits);
+        let app = Router::new()
+            .route(
+                "/api/v3/simple/price",
+                get(move |Query(q): Query<HashMap<String, String>>| {
+                    let hits = Arc::clone(&coingecko_hits);
+                    async move {
+                        hits.lock().expect("lock").push(format!(
+                            "vs_currencies={}",
+                            q.get("vs_currencies").cloned().unwrap_or_default()
+                        ));
+                        Json(serde_json::json!({ "ethereum": { "eur": 2400.5 } }))
+                    }
+                }),
+            )
+            .route(
+                "/v2/prices/ETH-EUR/spot",
+                get(move || {
+                    let hits = Arc::clone(&coinbase_hits);
+                    async move {
+                        hits.lock().expect("lock").push("/v2/prices/ETH-EUR/spot".to_string());
+                        Json(serde_json::json!({ "data": { "amount": "2399.10", "base": "ETH", "currency": "EUR" } }))
+                    }
+                }),
+            );
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let service = QuoteService::with_base_urls(
+            lazy_pool(),
+            format!("http://{}", addr),
+            format!("http://{}", addr),
+        );
+
+        let coingecko = service
+            .fetch_coingecko_price(FiatCurrency::Eur)
+            .await
+            .expect("coingecko price");
+        assert_eq!(
+            coingecko,
+            Decimal::from_str_exact("2400.5").expect("decimal")
+        );
+        let coinbase = service
+            .fetch_coinbase_price(FiatCurrency::Eur)
+            .await
+            .expect("coinbase price");
+        assert_eq!(
+            coinbase,
+            Decimal::from_str_exact("2399.10").expect("decimal")
+        );
+        assert_eq!(
+            hits.lock().expect("lock").as_slice(),
+            ["vs_currencies=eur", "/v2/prices/ETH-EUR/spot"]
+        );
+
+        let err = service
+            .fetch_coingecko_price(FiatCurrency::Gbp)
+            .await
+            .expect_err("no gbp price in the envelope");
+        assert!(matches!(err, ApiError::PriceUnavailable(_)));
+    }
+
+    #[test]
+    fn parses_supported_fiat_currencies() {
+        assert_eq!(FiatCurrency::parse("eur"), Some(FiatCurrency::Eur));
+        assert_eq!(FiatCurrency::parse(" GBP "), Some(FiatCurrency::Gbp));
+        assert_eq!(FiatCurrency::parse("USD"), Some(FiatCurrency::Usd));
+        assert_eq!(FiatCurrency::parse("JPY"), None);
+        assert_eq!(FiatCurrency::Eur.pair(), "ETH_EUR");
+    }
+
+    #[test]
+    fn stale_while_revalidate_decisions() {
+        let now = Utc::now();
+        let window = Duration::seconds(60);
+
+        assert_eq!(
+            cached_quote_use(now, Some(now + Duration::seconds(1)), window),
+            CachedQuoteUse::Serve
+        );
+        assert_eq!(
+            cached_quote_use(now, Some(now - Duration::seconds(30)), window),
+            CachedQuoteUse::ServeAndRefresh
+        );
+        assert_eq!(
+            cached_quote_use(now, Some(now - Duration::seconds(61)), window),
+            CachedQuoteUse::FetchLive
+        );
+        assert_eq!(
+            cached_quote_use(now, None, window),
+            CachedQuoteUse::FetchLive
+        );
+        assert_eq!(
+            cached_quote_use(now, Some(now + Duration::seconds(1)), Duration::zero()),
+            CachedQuoteUse::FetchLive
+        );
+    }
+
+    #[test]
+    fn takes_the_median_of_at_least_two_sources() {
+        let price = |raw: &str| Decimal::from_str_exact(raw).expect("decimal");
+        assert_eq!(
+            median_quote(vec![
+                ("coingecko", price("1300")),
+                ("coinbase", price("2600")),
+                ("kraken", price("2610")),
+            ]),
+            Some((
+                "median:coingecko,coinbase,kraken".to_string(),
+                price("2600")
+            ))
+        );
+        assert_eq!(
+            median_quote(vec![("coinbase", price("2600")), ("kraken", price("2601"))]),
+            Some(("median:coinbase,kraken".to_string(), price("2600.5")))
+        );
+        assert_eq!(median_quote(vec![("coinbase", price("2600"))]), None);
+    }
+
+    #[tokio::test]
+    async fn median_strategy_outvotes_a_single_outlier_source() {
+        let (coingecko, _) = spawn_flaky_source(
+            vec![],
+            serde_json::json!({ "ethereum": { "usd": 1300.25 } }),
+        )
+        .await;
+        let (coinbase, _) = spawn_flaky_source(
+            vec![],
+            serde_json::json!({ "data": { "amount": "2600.50" } }),
+        )
+        .await;
+        let (kraken, _) = spawn_flaky_source(
+            vec![],
+            serde_json::json!({ "error": [], "result": { "XETHZUSD": { "c": ["2601.10", "0.5"] } } }),
+        )
+        .await;
+        let mut service = QuoteService::with_base_urls(lazy_pool(), coingecko, coinbase);
+        service.kraken_base_url = kraken;
+        service.strategy = QuoteStrategy::Median;
+
+        let (source, price) = service
+            .fetch_median_price(FiatCurrency::Usd)
+            .await
+            .expect("median");
+        assert_eq!(source, "median:coingecko,coinbase,kraken");
+        assert_eq!(price, Decimal::from_str_exact("2600.50").expect("decimal"));
+
+        // With one source down the remaining two still qualify; with two down nothing does.
+        service.kraken_base_url = "http://127.0.0.1:9".to_string();
+        let (source, price) = service
+            .fetch_median_price(FiatCurrency::Usd)
+            .await
+            .expect("median of two");
+        assert_eq!(source, "median:coingecko,coinbase");
+        assert_eq!(price, Decimal::from_str_exact("1950.375").expect("decimal"));
+
+        service.coinbase_base_url = "http://127.0.0.1:9".to_string();
+        let err = service
+            .fetch_median_price(FiatCurrency::Usd)
+            .await
+            .expect_err("one source is not enough");
+        assert!(matches!(err, ApiError::PriceUnavailable(None)));
+    }
+
+    #[test]
+    fn rejects_cached_quotes_past_the_staleness_cutoff() {
+        let now = Utc::now();
+        let quote = |age_secs: i64| SpotQuoteRow {
+            id: Uuid::new_v4(),
+            source: "coingecko".to_string(),
+            price: Decimal::from(2600),
+            fetched_at: now - Duration::seconds(age_secs),
+            expires_at: now - Duration::seconds(age_secs) + Duration::minutes(5),
+        };
+        let max = Some(Duration::hours(1));
+
+        let fresh = cached_selection(Some(quote(60)), now, max).expect("fresh quote");
+        assert!(fresh.from_cache);
+        assert!(!fresh.stale);
+
+        let near_stale = cached_selection(Some(quote(3_000)), now, max).expect("within cutoff");
+        assert!(near_stale.stale);
+        assert!(cached_selection(Some(quote(3_600)), now, max).is_ok());
+
+        let too_old = quote(3_601);
+        let fetched_at = too_old.fetched_at;
+        let err = cached_selection(Some(too_old), now, max).expect_err("past cutoff");
+        assert!(matches!(err, ApiError::PriceUnavailable(Some(at)) if at == fetched_at));
+
+        assert!(cached_selection(Some(quote(86_400)), now, None).is_ok());
+        assert!(matches!(
+            cached_selection(None, now, max),
+            Err(ApiError::PriceUnavailable(None))
+        ));
+    }
+
+    #[tokio::test]
+    async fn fastest_strategy_takes_the_first_source_to_answer() {
+        let slow = Router::new().fallback(|| async {
+            tokio::time::sleep(StdDuration::from_secs(2)).await;
+            Json(serde_json::json!({ "ethereum": { "usd": 1999.00 } }))
+        });
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let slow_url = format!("http://{}", listener.local_addr().expect("addr"));
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, slow).await;
+        });
+        let (fast_url, _) = spawn_flaky_source(
+            vec![],
+            serde_json::json!({ "data": { "amount": "2600.50" } }),
+        )
+        .await;
+        let mut service = QuoteService::with_base_urls(lazy_pool(), slow_url, fast_url);
+        service.strategy = QuoteStrategy::Fastest;
+
+        let started = std::time::Instant::now();
+        let (source, price) = service
+            .fetch_fastest_price(FiatCurrency::Usd)
+            .await
+            .expect("fastest");
+        assert_eq!(source, "coinbase");
+        assert_eq!(price, Decimal::from_str_exact("2600.50").expect("decimal"));
+        assert!(started.elapsed() < StdDuration::from_secs(1));
+    }
+
+    /// Price source stub answering the n-th request with `statuses[n]` (200 once exhausted);
+    /// returns its base URL and a hit counter.
+    async fn spawn_flaky_source(
+        statuses: Vec<u16>,
+        body: serde_json::Value,
+    ) -> (String, Arc<Mutex<usize>>) {
+        let hits = Arc::new(Mutex::new(0usize));
+        let hits_clone = Arc::clone(&hits);
+        let app = Router::new().fallback(move || {
+            let hits = Arc::clone(&hits_clone);
+            let statuses = statuses.clone();
+            let body = body.clone();
+            async move {
+                let mut hits = hits.lock().expect("lock");
+                let status = statuses.get(*hits).copied().unwrap_or(200);
+                *hits += 1;
+                (
+                    axum::http::StatusCode::from_u16(status).expect("status"),
+                    Json(body),
+                )
+            }
+        });
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+        (format!("http://{}", addr), hits)
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_before_giving_up_on_a_source() {
+        let (base_url, hits) = spawn_flaky_source(
+            vec![503],
+            serde_json::json!({ "ethereum": { "usd": 2010.50 } }),
+        )
+        .await;
+        let service =
+            QuoteService::with_base_urls(lazy_pool(), base_url, "http://127.0.0.1:9".to_string());
+
+        let price = service
+            .fetch_coingecko_price(FiatCurrency::Usd)
+            .await
+            .expect("price after retry");
+        assert_eq!(price, Decimal::from_str_exact("2010.50").expect("decimal"));
+        assert_eq!(*hits.lock().expect("lock"), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        let (base_url, hits) = spawn_flaky_source(vec![429], serde_json::json!({})).await;
+        let service =
+            QuoteService::with_base_urls(lazy_pool(), "http://127.0.0.1:9".to_string(), base_url);
+
+        let err = service
+            .fetch_coinbase_price(FiatCurrency::Usd)
+            .await
+            .expect_err("4xx");
+        assert!(matches!(err, ApiError::PriceUnavailable(None)));
+        assert_eq!(*hits.lock().expect("lock"), 1);
+    }
+
+    #[tokio::test]
+    async fn open_breaker_skips_a_failing_source_until_the_cooldown_elapses() {
+        let (base_url, hits) = spawn_flaky_source(
+            vec![404, 404],
+            serde_json::json!({ "ethereum": { "usd": 2010.50 } }),
+        )
+        .await;
+        let mut service =
+            QuoteService::with_base_urls(lazy_pool(), base_url, "http://127.0.0.1:9".to_string());
+        service.breaker_failure_threshold = 2;
+        service.breaker_cooldown = StdDuration::from_millis(300);
+        let fetch = |service: &QuoteService| {
+            let service = service.clone();
+            async move {
+                service
+                    .guarded(
+                        "coingecko",
+                        service.fetch_coingecko_price(FiatCurrency::Usd),
+                    )
+                    .await
+            }
+        };
+
+        fetch(&service).await.expect_err("first failure");
+        fetch(&service).await.expect_err("second failure opens");
+        assert_eq!(*hits.lock().expect("lock"), 2);
+
+        let err = fetch(&service).await.expect_err("open breaker");
+        assert!(matches!(err, ApiError::PriceUnavailable(None)));
+        assert_eq!(
+            *hits.lock().expect("lock"),
+            2,
+            "open breaker must not call the source"
+        );
+
+        tokio::time::sleep(StdDuration::from_millis(350)).await;
+        let price = fetch(&service).await.expect("half-open probe succeeds");
+        assert_eq!(price, Decimal::from_str_exact("2010.50").expect("decimal"));
+        assert_eq!(*hits.lock().expect("lock"), 3);
+        assert!(service.breakers.lock().expect("lock").is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_probe_reopens_the_breaker_for_another_cooldown() {
+        let mut service = QuoteService::with_base_urls(
+            lazy_pool(),
+            "http://127.0.0.1:9".to_string(),
+            "http://127.0.0.1:9".to_string(),
+        );
+        service.breaker_failure_threshold = 2;
+        service.breaker_cooldown = StdDuration::from_secs(30);
+        let start = Instant::now();
+
+        service.record_source_outcome("kraken", false, start);
+        assert!(service.breaker_allows("kraken", start));
+        service.record_source_outcome("kraken", false, start);
+        assert!(!service.breaker_allows("kraken", start + StdDuration::from_secs(29)));
+        assert!(service.breaker_allows("coinbase", start));
+
+        let probe_at = start + StdDuration::from_secs(30);
+        assert!(service.breaker_allows("kraken", probe_at));
+        assert!(
+            !service.breaker_allows("kraken", probe_at),
+            "only one probe per cooldown"
+        );
+        service.record_source_outcome("kraken", false, probe_at);
+        assert!(!service.breaker_allows("kraken", probe_at + StdDuration::from_secs(29)));
+        assert!(service.breaker_allows("kraken", probe_at + StdDuration::from_secs(30)));
+
+        service.breaker_failure_threshold = 0;
+        assert!(service.breaker_allows("kraken", probe_at));
+    }
+
     #[tokio::test]
     #[ignore = "live network test; run explicitly"]
     async fn live_coingecko_endpoint_returns_price() {
-        let service = QuoteService::new(lazy_pool());
+        let service = QuoteService::new(lazy_pool(), &Config::for_tests());
         let price = service
-            .fetch_coingecko_price()
+            .fetch_coingecko_price(FiatCurrency::Usd)
             .await
             .expect("live coingecko price");
         assert!(price > Decimal::ZERO);
@@ -325,11 +1156,22 @@ mod tests {
     #[tokio::test]
     #[ignore = "live network test; run explicitly"]
     async fn live_coinbase_endpoint_returns_price() {
-        let service = QuoteService::new(lazy_pool());
+        let service = QuoteService::new(lazy_pool(), &Config::for_tests());
         let price = service
-            .fetch_coinbase_price()
+            .fetch_coinbase_price(FiatCurrency::Usd)
             .await
             .expect("live coinbase price");
         assert!(price > Decimal::ZERO);
     }
+
+    #[tokio::test]
+    #[ignore = "live network test; run explicitly"]
+    async fn live_kraken_endpoint_returns_price() {
+        let service = QuoteService::new(lazy_pool(), &Config::for_tests());
+        let price = service
+            .fetch_kraken_price(FiatCurrency::Usd)
+            .await
+            .expect("live kraken price");
+        assert!(price > Decimal::ZERO);
+    }
 }
@@ -1,46 +1,342 @@
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+    collections::{HashMap, VecDeque},
+    future::{Future, ready},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use chrono::Utc;
+use redis::aio::{ConnectionManager, ConnectionManagerConfig};
+use tokio::sync::OnceCell;
 
-use crate::error::{ApiError, ApiResult};
+use crate::{
+    config::RateLimit,
+    error::{ApiError, ApiResult},
+};
 
-#[derive(Clone)]
-pub struct RateLimiter {
-    inner: std::sync::Arc<Mutex<HashMap<String, RateWindow>>>,
+/// Redis keys are namespaced so the limiter can share an instance with other tenants.
+const REDIS_KEY_PREFIX: &str = "sitg:rl:";
+/// In-memory limiters keep at most this many keys, evicting the least recently hit beyond it.
+const DEFAULT_MAX_ENTRIES: usize = 100_000;
+/// Entries untouched for this many windows are dropped by the sweep.
+const STALE_AFTER_WINDOWS: i64 = 2;
+/// In-memory limiters sweep stale entries at most this often, from inside `check`.
+const SWEEP_INTERVAL_MS: i64 = 60_000;
+/// Checks run on the request path, so a slow or unreachable Redis must fail fast.
+const REDIS_TIMEOUT: Duration = Duration::from_millis(250);
+/// Sliding-window log in a sorted set scored by hit time (ms): drop hits older than the window,
+/// refuse if `max` remain, otherwise record this one. Runs atomically on the Redis server.
+/// KEYS[1] = log key; ARGV = now_ms, window_ms, max, unique member.
+/// Returns `{allowed, hits in window, score of the hit whose expiry frees the next slot}`.
+const REDIS_SLIDING_WINDOW_SCRIPT: &str = r#"
+local now = tonumber(ARGV[1])
+local max = tonumber(ARGV[3])
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now - tonumber(ARGV[2]))
+local count = redis.call('ZCARD', KEYS[1])
+if count >= max then
+  local blocking = redis.call('ZRANGE', KEYS[1], count - max, count - max, 'WITHSCORES')
+  return {0, count, tonumber(blocking[2] or now)}
+end
+redis.call('ZADD', KEYS[1], now, ARGV[4])
+redis.call('PEXPIRE', KEYS[1], ARGV[2])
+local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+return {1, count + 1, tonumber(oldest[2])}
+"#;
+
+/// What a backend saw when asked to record a hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateHit {
+    pub allowed: bool,
+    /// Hits recorded in the trailing window, this one included if allowed.
+    pub count: u32,
+    /// Until the hit holding the next slot slides out of the window.
+    pub reset_after_ms: i64,
 }
 
-#[derive(Debug, Clone)]
-struct RateWindow {
-    started_at_unix: i64,
-    count: u32,
+/// Quota left after an allowed call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateDecision {
+    pub remaining: u32,
+    /// Until the oldest call in the window stops counting and frees a slot.
+    pub reset_after_secs: u64,
+}
+
+/// Boxed so backends can sit behind `Arc<dyn RateBackend>`.
+pub type RateHitFuture<'a> = Pin<Box<dyn Future<Output = anyhow::Result<RateHit>> + Send + 'a>>;
+
+/// Where hit logs live. `try_hit` records a hit against `key` if fewer than `max` hits were
+/// recorded in the trailing `window_secs` seconds. Rejected hits are not recorded.
+pub trait RateBackend: Send + Sync {
+    fn try_hit<'a>(&'a self, key: &'a str, max: u32, window_secs: i64) -> RateHitFuture<'a>;
+}
+
+#[derive(Clone)]
+pub struct RateLimiter {
+    backend: Arc<dyn RateBackend>,
+    /// Counts locally while the shared backend is failing, so limits still apply per replica.
+    fallback: Arc<MemoryBackend>,
 }
 
 impl RateLimiter {
     pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_MAX_ENTRIES)
+    }
+
+    /// In-memory limiter tracking at most `max_entries` keys.
+    pub fn with_capacity(max_entries: usize) -> Self {
+        Self::with_backend(Arc::new(MemoryBackend::with_capacity(max_entries)))
+    }
+
+    pub fn with_backend(backend: Arc<dyn RateBackend>) -> Self {
         Self {
-            inner: std::sync::Arc::new(Mutex::new(HashMap::new())),
+            backend,
+            fallback: Arc::new(MemoryBackend::with_capacity(DEFAULT_MAX_ENTRIES)),
+        }
+    }
+
+    /// Shares counters across replicas through Redis when `redis_url` is set; counts in memory
+    /// otherwise.
+    pub fn from_redis_url(redis_url: Option<&str>) -> anyhow::Result<Self> {
+        match redis_url {
+            Some(url) => Ok(Self::with_backend(Arc::new(RedisBackend::open(url)?))),
+            None => Ok(Self::new()),
+        }
+    }
+
+    /// `check` with a configured budget.
+    pub async fn check_limit(&self, key: &str, limit: RateLimit) -> ApiResult<RateDecision> {
+        self.check(key, limit.max, limit.window_secs).await
+    }
+
+    /// Allows at most `max` calls per `key` over any trailing `window_secs` interval; rejects
+    /// the rest with `RATE_LIMITED` and how long to wait.
+    pub async fn check(&self, key: &str, max: u32, window_secs: i64) -> ApiResult<RateDecision> {
+        let hit = match self.backend.try_hit(key, max, window_secs).await {
+            Ok(hit) => hit,
+            Err(err) => {
+                tracing::warn!(error = %err, key, "rate limit backend failed; counting locally");
+                self.fallback
+                    .try_hit(key, max, window_secs)
+                    .await
+                    .map_err(|_| ApiError::Forbidden)?
+            }
+        };
+
+        // Round up so a client honouring the advice does not come back a moment too early.
+        let reset_after_secs = (hit.reset_after_ms.max(0) as u64).div_ceil(1000);
+        if !hit.allowed {
+            return Err(ApiError::RateLimited {
+                retry_after_secs: reset_after_secs.max(1),
+            });
+        }
+        Ok(RateDecision {
+            remaining: max.saturating_sub(hit.count),
+            reset_after_secs,
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryBackend {
+    inner: Mutex<MemoryWindows>,
+    /// When set, the least recently hit keys are evicted to stay at this many entries.
+    max_entries: Option<usize>,
+}
+
+#[derive(Default)]
+struct MemoryWindows {
+    windows: HashMap<String, RateWindow>,
+    last_sweep_ms: i64,
+}
+
+/// Times (unix ms) of the hits recorded in the trailing window, oldest first.
+#[derive(Debug, Clone, Default)]
+struct RateWindow {
+    hits_ms: VecDeque<i64>,
+    window_ms: i64,
+    last_seen_ms: i64,
+}
+
+impl RateWindow {
+    fn try_hit(&mut self, now_ms: i64, max: u32, window_ms: i64) -> RateHit {
+        self.window_ms = window_ms;
+        self.last_seen_ms = now_ms;
+        while self
+            .hits_ms
+            .front()
+            .is_some_and(|&hit| hit <= now_ms - window_ms)
+        {
+            self.hits_ms.pop_front();
+        }
+        let allowed = self.hits_ms.len() < max as usize;
+        if allowed {
+            self.hits_ms.push_back(now_ms);
+        }
+        // A slot frees up when the hit `max` places from the newest leaves the window.
+        let blocking = if allowed {
+            0
+        } else {
+            self.hits_ms.len() - max as usize
+        };
+        let reset_at = self.hits_ms.get(blocking).copied().unwrap_or(now_ms) + window_ms;
+        RateHit {
+            allowed,
+            count: u32::try_from(self.hits_ms.len()).unwrap_or(u32::MAX),
+            reset_after_ms: reset_at - now_ms,
         }
     }
 
-    pub fn check(&self, key: &str, max: u32, window_secs: i64) -> ApiResult<()> {
-        let now = Utc::now().timestamp();
-        let mut map = self.inner.lock().map_err(|_| ApiError::Forbidden)?;
-        let entry = map.entry(key.to_string()).or_insert(RateWindow {
-            started_at_unix: now,
-            count: 0,
-        });
+    /// Every recorded hit has long slid out of the window, so the entry limits nothing.
+    fn is_stale(&self, now_ms: i64) -> bool {
+        now_ms - self.last_seen_ms > self.window_ms.saturating_mul(STALE_AFTER_WINDOWS)
+    }
+}
 
-        if now - entry.started_at_unix >= window_secs {
-            entry.started_at_unix = now;
-            entry.count = 0;
+impl MemoryBackend {
+    pub fn with_capacity(max_entries: usize) -> Self {
+        Self {
+            max_entries: Some(max_entries.max(1)),
+            ..Self::default()
         }
+    }
+
+    fn try_hit_at(
+        &self,
+        key: &str,
+        max: u32,
+        window_secs: i64,
+        now_ms: i64,
+    ) -> anyhow::Result<RateHit> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_| anyhow::anyhow!("rate limiter lock poisoned"))?;
+        if now_ms - inner.last_sweep_ms >= SWEEP_INTERVAL_MS {
+            inner.sweep(now_ms);
+        }
+        if let Some(max_entries) = self.max_entries
+            && !inner.windows.contains_key(key)
+            && inner.windows.len() >= max_entries
+        {
+            inner.sweep(now_ms);
+            let overflow = (inner.windows.len() + 1).saturating_sub(max_entries);
+            inner.evict_least_recent(overflow);
+        }
+        Ok(inner.windows.entry(key.to_string()).or_default().try_hit(
+            now_ms,
+            max,
+            window_secs.saturating_mul(1000),
+        ))
+    }
 
-        if entry.count >= max {
-            return Err(ApiError::Conflict("RATE_LIMITED"));
+    #[cfg(test)]
+    fn sweep_at(&self, now_ms: i64) {
+        self.inner.lock().expect("rate limiter lock").sweep(now_ms);
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.inner.lock().expect("rate limiter lock").windows.len()
+    }
+}
+
+impl MemoryWindows {
+    fn sweep(&mut self, now_ms: i64) {
+        self.windows.retain(|_, window| !window.is_stale(now_ms));
+        self.last_sweep_ms = now_ms;
+    }
+
+    /// Evicts the `count` least recently hit keys.
+    fn evict_least_recent(&mut self, count: usize) {
+        let count = count.min(self.windows.len());
+        if count == 0 {
+            return;
+        }
+        let mut by_age: Vec<(i64, String)> = self
+            .windows
+            .iter()
+            .map(|(key, window)| (window.last_seen_ms, key.clone()))
+            .collect();
+        by_age.select_nth_unstable(count - 1);
+        for (_, key) in by_age.into_iter().take(count) {
+            self.windows.remove(&key);
         }
+    }
+}
 
-        entry.count += 1;
-        Ok(())
+impl RateBackend for MemoryBackend {
+    fn try_hit<'a>(&'a self, key: &'a str, max: u32, window_secs: i64) -> RateHitFuture<'a> {
+        Box::pin(ready(self.try_hit_at(
+            key,
+            max,
+            window_secs,
+            Utc::now().timestamp_millis(),
+        )))
+    }
+}
+
+/// Sliding-window logs in Redis (`REDIS_SLIDING_WINDOW_SCRIPT`), so all replicas enforce the
+/// same trailing window. Requests share one multiplexed connection that reconnects on its own
+/// after a failure, so no check waits on another.
+pub struct RedisBackend {
+    client: redis::Client,
+    conn: OnceCell<ConnectionManager>,
+}
+
+impl RedisBackend {
+    pub fn open(url: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: redis::Client::open(url)?,
+            conn: OnceCell::new(),
+        })
+    }
+
+    /// Connects on first use, since the limiter is built outside the runtime's async context.
+    async fn connection(&self) -> redis::RedisResult<ConnectionManager> {
+        let conn = self
+            .conn
+            .get_or_try_init(|| {
+                let config = ConnectionManagerConfig::new()
+                    .set_connection_timeout(REDIS_TIMEOUT)
+                    .set_response_timeout(REDIS_TIMEOUT)
+                    .set_number_of_retries(1);
+                ConnectionManager::new_with_config(self.client.clone(), config)
+            })
+            .await?;
+        Ok(conn.clone())
+    }
+
+    async fn eval_hit(&self, key: &str, max: u32, window_secs: i64) -> anyhow::Result<RateHit> {
+        let mut conn = self.connection().await?;
+        let now_ms = Utc::now().timestamp_millis();
+        let window_ms = window_secs.max(1).saturating_mul(1000);
+        let (allowed, count, reset_from_ms): (i64, u32, i64) = redis::cmd("EVAL")
+            .arg(REDIS_SLIDING_WINDOW_SCRIPT)
+            .arg(1)
+            .arg(format!("{REDIS_KEY_PREFIX}{key}"))
+            .arg(now_ms)
+            .arg(window_ms)
+            .arg(max)
+            .arg(format!("{now_ms}-{}", rand::random::<u64>()))
+            .query_async(&mut conn)
+            .await?;
+        Ok(RateHit {
+            allowed: allowed == 1,
+            count,
+            reset_after_ms: reset_from_ms + window_ms - now_ms,
+        })
+    }
+}
+
+impl RateBackend for RedisBackend {
+    fn try_hit<'a>(&'a self, key: &'a str, max: u32, window_secs: i64) -> RateHitFuture<'a> {
+        Box::pin(async move {
+            // Covers connecting too, which the connection manager may retry.
+            tokio::time::timeout(REDIS_TIMEOUT, self.eval_hit(key, max, window_secs))
+                .await
+                .map_err(|_| anyhow::anyhow!("redis rate limit check timed out"))?
+        })
     }
 }
 
@@ -48,34 +344,249 @@ impl RateLimiter {
 mod tests {
     use super::*;
 
-    #[test]
-    fn enforces_limit() {
+    /// Stands in for a shared store: counts per key, windows never roll over.
+    #[derive(Default)]
+    struct MockBackend {
+        hits: Mutex<HashMap<String, u32>>,
+    }
+
+    impl RateBackend for MockBackend {
+        fn try_hit<'a>(&'a self, key: &'a str, max: u32, window_secs: i64) -> RateHitFuture<'a> {
+            let mut hits = self.hits.lock().expect("mock lock");
+            let count = hits.entry(key.to_string()).or_default();
+            let allowed = *count < max;
+            if allowed {
+                *count += 1;
+            }
+            Box::pin(ready(Ok(RateHit {
+                allowed,
+                count: *count,
+                reset_after_ms: window_secs * 1000,
+            })))
+        }
+    }
+
+    struct FailingBackend;
+
+    impl RateBackend for FailingBackend {
+        fn try_hit<'a>(&'a self, _key: &'a str, _max: u32, _window_secs: i64) -> RateHitFuture<'a> {
+            Box::pin(ready(Err(anyhow::anyhow!("backend unavailable"))))
+        }
+    }
+
+    #[tokio::test]
+    async fn enforces_limit() {
         let limiter = RateLimiter::new();
-        limiter.check("u:1", 2, 60).expect("first");
-        limiter.check("u:1", 2, 60).expect("second");
-        let err = limiter.check("u:1", 2, 60).expect_err("third should fail");
-        assert!(matches!(err, ApiError::Conflict("RATE_LIMITED")));
+        limiter.check("u:1", 2, 60).await.expect("first");
+        limiter.check("u:1", 2, 60).await.expect("second");
+        let err = limiter
+            .check("u:1", 2, 60)
+            .await
+            .expect_err("third should fail");
+        assert!(matches!(err, ApiError::RateLimited { .. }));
     }
 
-    #[test]
-    fn resets_counter_when_window_has_elapsed() {
+    #[tokio::test]
+    async fn reports_remaining_quota_and_when_to_retry() {
+        let backend = MemoryBackend::default();
+        let first = backend.try_hit_at("u:1", 3, 60, 10_000).expect("first");
+        assert_eq!(
+            (first.allowed, first.count, first.reset_after_ms),
+            (true, 1, 60_000)
+        );
+        let second = backend.try_hit_at("u:1", 3, 60, 25_000).expect("second");
+        assert_eq!((second.count, second.reset_after_ms), (2, 45_000));
+        backend.try_hit_at("u:1", 3, 60, 40_000).expect("third");
+
+        // Full: the next slot frees when the t=10s hit leaves the window at t=70s.
+        let limited = backend.try_hit_at("u:1", 3, 60, 50_000).expect("fourth");
+        assert_eq!((limited.allowed, limited.reset_after_ms), (false, 20_000));
+
         let limiter = RateLimiter::new();
-        limiter.check("u:1", 1, 0).expect("first");
+        let decision = limiter.check("u:1", 2, 60).await.expect("first");
+        assert_eq!(decision.remaining, 1);
+        assert!((59..=60).contains(&decision.reset_after_secs));
+        assert_eq!(
+            limiter.check("u:1", 2, 60).await.expect("second").remaining,
+            0
+        );
+        let err = limiter.check("u:1", 2, 60).await.expect_err("third");
+        assert!(matches!(
+            err,
+            ApiError::RateLimited { retry_after_secs } if (59..=60).contains(&retry_after_secs)
+        ));
+    }
+
+    #[tokio::test]
+    async fn resets_counter_when_window_has_elapsed() {
+        let limiter = RateLimiter::new();
+        limiter.check("u:1", 1, 0).await.expect("first");
         limiter
             .check("u:1", 1, 0)
+            .await
             .expect("window reset should allow second call");
     }
 
-    #[test]
-    fn tracks_limits_per_key() {
+    #[tokio::test]
+    async fn tracks_limits_per_key() {
         let limiter = RateLimiter::new();
-        limiter.check("u:1", 1, 60).expect("first key");
+        limiter.check("u:1", 1, 60).await.expect("first key");
         let err = limiter
             .check("u:1", 1, 60)
+            .await
             .expect_err("first key should be limited");
-        assert!(matches!(err, ApiError::Conflict("RATE_LIMITED")));
+        assert!(matches!(err, ApiError::RateLimited { .. }));
         limiter
             .check("u:2", 1, 60)
+            .await
             .expect("second key should not be limited");
     }
+
+    #[test]
+    fn rejects_bursts_straddling_a_window_boundary() {
+        let backend = MemoryBackend::default();
+        let hit = |at_secs: i64| {
+            backend
+                .try_hit_at("wallet:confirm:u1", 30, 60, at_secs * 1000)
+                .expect("memory backend")
+                .allowed
+        };
+
+        assert!((0..30).all(|_| hit(59)), "30 calls at t=59s fit the limit");
+        assert!(
+            (0..30).all(|_| !hit(61)),
+            "30 more at t=61s are still inside the trailing 60s"
+        );
+        assert!(
+            !hit(118),
+            "the t=59s burst is still in the window at t=118s"
+        );
+        assert!(hit(119), "and has slid out of it at t=119s");
+    }
+
+    #[test]
+    fn rejected_calls_do_not_extend_the_window() {
+        let backend = MemoryBackend::default();
+        assert!(backend.try_hit_at("u:1", 1, 60, 0).expect("first").allowed);
+        assert!(
+            !backend
+                .try_hit_at("u:1", 1, 60, 30_000)
+                .expect("limited")
+                .allowed
+        );
+        assert!(
+            backend
+                .try_hit_at("u:1", 1, 60, 60_000)
+                .expect("window slid")
+                .allowed
+        );
+    }
+
+    #[test]
+    fn sweep_drops_keys_whose_hits_have_slid_out() {
+        let backend = MemoryBackend::default();
+        for user in 0..10_000 {
+            backend
+                .try_hit_at(&format!("wallet:confirm:{user}"), 5, 60, 1_000)
+                .expect("hit");
+        }
+        backend
+            .try_hit_at("wallet:confirm:active", 5, 60, 100_000)
+            .expect("recent hit");
+        assert_eq!(backend.len(), 10_001);
+
+        backend.sweep_at(100_000);
+        assert_eq!(
+            backend.len(),
+            10_001,
+            "hits from 99s ago are within two windows"
+        );
+        backend.sweep_at(150_000);
+        assert_eq!(backend.len(), 1, "only the recently hit key survives");
+    }
+
+    #[test]
+    fn check_sweeps_stale_keys_lazily() {
+        let backend = MemoryBackend::default();
+        for user in 0..10_000 {
+            backend
+                .try_hit_at(&format!("u:{user}"), 5, 60, SWEEP_INTERVAL_MS)
+                .expect("hit");
+        }
+        backend
+            .try_hit_at("u:late", 5, 60, SWEEP_INTERVAL_MS * 4)
+            .expect("late hit");
+        assert_eq!(backend.len(), 1);
+    }
+
+    #[test]
+    fn capacity_evicts_the_least_recently_hit_keys() {
+        let backend = MemoryBackend::with_capacity(100);
+        for n in 0..150 {
+            backend
+                .try_hit_at(&format!("u:{n}"), 1, 60, n)
+                .expect("hit");
+        }
+        assert_eq!(backend.len(), 100);
+        assert!(
+            !backend
+                .try_hit_at("u:149", 1, 60, 200)
+                .expect("recent key")
+                .allowed,
+            "recently hit keys keep their counts"
+        );
+        assert!(
+            backend
+                .try_hit_at("u:0", 1, 60, 201)
+                .expect("evicted key")
+                .allowed,
+            "the oldest keys were evicted"
+        );
+        assert_eq!(backend.len(), 100);
+    }
+
+    #[tokio::test]
+    async fn limiters_sharing_a_backend_share_counts() {
+        let shared: Arc<dyn RateBackend> = Arc::new(MockBackend::default());
+        let replica_a = RateLimiter::with_backend(shared.clone());
+        let replica_b = RateLimiter::with_backend(shared);
+
+        replica_a.check("u:1", 2, 60).await.expect("first on a");
+        replica_b.check("u:1", 2, 60).await.expect("second on b");
+        let err = replica_a
+            .check("u:1", 2, 60)
+            .await
+            .expect_err("third across replicas should fail");
+        assert!(matches!(err, ApiError::RateLimited { .. }));
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_local_counts_when_the_backend_fails() {
+        let limiter = RateLimiter::with_backend(Arc::new(FailingBackend));
+        limiter.check("u:1", 1, 60).await.expect("first");
+        let err = limiter
+            .check("u:1", 1, 60)
+            .await
+            .expect_err("local fallback still limits");
+        assert!(matches!(err, ApiError::RateLimited { .. }));
+    }
+
+    #[tokio::test]
+    async fn unreachable_redis_falls_back_without_stalling_the_request() {
+        let limiter = RateLimiter::from_redis_url(Some("redis://127.0.0.1:1")).expect("valid url");
+        let started = std::time::Instant::now();
+        limiter.check("u:1", 1, 60).await.expect("first");
+        let err = limiter
+            .check("u:1", 1, 60)
+            .await
+            .expect_err("local fallback still limits");
+        assert!(matches!(err, ApiError::RateLimited { .. }));
+        assert!(started.elapsed() < REDIS_TIMEOUT * 4);
+    }
+
+    #[test]
+    fn rejects_a_malformed_redis_url() {
+        assert!(RateLimiter::from_redis_url(Some("not a url")).is_err());
+        assert!(RateLimiter::from_redis_url(None).is_ok());
+    }
 }
@@ -17,8 +17,10 @@ pub fn recover_personal_sign_address(message: &str, signature_hex: &str) -> ApiR
     Ok(format!("{:#x}", recovered))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn recover_eip712_pr_confirmation_address(
     chain_id: u64,
+    domain_version: &str,
     verifying_contract: &str,
     github_user_id: i64,
     github_repo_id: i64,
@@ -33,6 +35,37 @@ pub fn recover_eip712_pr_confirmation_address(
         .parse()
         .map_err(|_| ApiError::validation("signature is not valid hex signature"))?;
 
+    let digest = pr_confirmation_digest(
+        chain_id,
+        domain_version,
+        verifying_contract,
+        github_user_id,
+        github_repo_id,
+        pull_request_number,
+        head_sha,
+        challenge_id,
+        nonce,
+        expires_at,
+    )?;
+    let recovered: H160 = signature
+        .recover(digest)
+        .map_err(|_| ApiError::validation("signature recovery failed"))?;
+    Ok(format!("{:#x}", recovered))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn pr_confirmation_digest(
+    chain_id: u64,
+    domain_version: &str,
+    verifying_contract: &str,
+    github_user_id: i64,
+    github_repo_id: i64,
+    pull_request_number: i32,
+    head_sha: &str,
+    challenge_id: &str,
+    nonce: &str,
+    expires_at: i64,
+) -> ApiResult<[u8; 32]> {
     let typed_data = serde_json::json!({
       "types": {
         "EIP712Domain": [
@@ -54,7 +87,7 @@ pub fn recover_eip712_pr_confirmation_address(
       "primaryType": "PRGateConfirmation",
       "domain": {
         "name": "SITG",
-        "version": "1",
+        "version": domain_version,
         "chainId": chain_id,
         "verifyingContract": verifying_contract
       },
@@ -72,13 +105,9 @@ pub fn recover_eip712_pr_confirmation_address(
     let typed_data: ethers_core::types::transaction::eip712::TypedData =
         serde_json::from_value(typed_data)
             .map_err(|_| ApiError::validation("failed to construct typed data"))?;
-    let digest = typed_data
+    typed_data
         .encode_eip712()
-        .map_err(|_| ApiError::validation("failed to hash typed data"))?;
-    let recovered: H160 = signature
-        .recover(digest)
-        .map_err(|_| ApiError::validation("signature recovery failed"))?;
-    Ok(format!("{:#x}", recovered))
+        .map_err(|_| ApiError::validation("failed to hash typed data"))
 }
 
 pub fn uuid_to_bytes32_hex(id: uuid::Uuid) -> String {
@@ -95,6 +124,38 @@ pub fn uuid_to_uint256_decimal(id: uuid::Uuid) -> String {
     bigint.to_string()
 }
 
+#[cfg(test)]
+pub mod test_support {
+    use ethers_core::{
+        k256::ecdsa::SigningKey,
+        types::{Signature, U256},
+        utils::{hash_message, secret_key_to_address},
+    };
+
+    pub fn test_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32].into()).expect("valid test key")
+    }
+
+    pub fn address_of(key: &SigningKey) -> String {
+        format!("{:#x}", secret_key_to_address(key))
+    }
+
+    pub fn sign_personal_message(key: &SigningKey, message: &str) -> String {
+        sign_digest(key, hash_message(message).as_bytes())
+    }
+
+    pub fn sign_digest(key: &SigningKey, digest: &[u8]) -> String {
+        let (signature, recovery_id) = key.sign_prehash_recoverable(digest).expect("sign digest");
+        let bytes = signature.to_bytes();
+        let signature = Signature {
+            r: U256::from_big_endian(&bytes[..32]),
+            s: U256::from_big_endian(&bytes[32..]),
+            v: u64::from(recovery_id.to_byte()) + 27,
+        };
+        format!("0x{signature}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,4 +181,12 @@ mod tests {
             .expect_err("invalid signature should fail");
         assert!(matches!(err, ApiError::Validation(_)));
     }
+
+    #[test]
+    fn recovers_personal_sign_address() {
+        let key = test_support::test_key(7);
+        let signature = test_support::sign_personal_message(&key, "hello");
+        let recovered = recover_personal_sign_address("hello", &signature).expect("recover");
+        assert_eq!(recovered, test_support::address_of(&key));
+    }
 }
@@ -1,115 +1,362 @@
-use std::str::FromStr;
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
+use ethers_core::{
+    abi::{self, ParamType, Token},
+    types::Address,
+};
+use rand::Rng;
 use reqwest::Client;
 use serde::Deserialize;
 use serde_json::json;
 use sha3::{Digest, Keccak256};
+use tokio::{sync::Semaphore, task::JoinSet};
 
 use crate::{
-    config::Config,
+    config::{Config, StakeLockMode, StakePositionMode},
     error::{ApiError, ApiResult},
+    services::http_client::outbound_client,
 };
 
+/// Upper bound on in-flight stake lookups for a single batch request.
+const STAKE_BATCH_CONCURRENCY: usize = 8;
+/// Upper bound on lock positions read for one wallet under `StakePositionMode::Positions`.
+const MAX_STAKE_POSITIONS: u128 = 32;
+/// Passes over the whole RPC endpoint list before an `eth_call` gives up.
+const RPC_MAX_ROUNDS: u32 = 2;
+/// Pause between rounds: this base plus up to as much again in jitter, so instances that failed
+/// together do not retry together.
+const RPC_ROUND_BACKOFF: Duration = Duration::from_millis(100);
+/// Multicall3 `aggregate3((address,bool,bytes)[])`.
+const AGGREGATE3_SIGNATURE: &str = "aggregate3((address,bool,bytes)[])";
+
 #[derive(Clone)]
 pub struct StakeService {
     client: Client,
-    rpc_url: Option<String>,
+    /// Tried in order on each call; see `eth_call`.
+    rpc_urls: Vec<String>,
     contract_address: Option<String>,
+    /// ERC-20 whose `balanceOf` is the stake; `None` reads the contract's native `stakedBalance`.
+    token_address: Option<String>,
+    multicall3_address: Option<String>,
     blocked_unlink_wallets: Vec<String>,
+    lock_mode: StakeLockMode,
+    position_mode: StakePositionMode,
+    /// `None` disables the stake status cache.
+    cache_ttl: Option<Duration>,
+    /// Lowercased wallet address to the status read at the paired instant.
+    cache: Arc<Mutex<HashMap<String, (Instant, StakeStatus)>>>,
 }
 
 #[derive(Debug, Deserialize)]
-struct EthCallResponse {
+struct RpcResponse {
     result: String,
 }
 
+/// One lock position as returned by `stakeAt(address, uint256)`: `(amount, unlockTime)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StakePosition {
+    amount_wei: u128,
+    unlock_time_unix: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct StakeStatus {
     pub balance_wei: u128,
-    pub unlock_time_unix: u64,
+    /// `None` under `StakeLockMode::Disabled`, where the contract has no time-lock to read.
+    pub unlock_time_unix: Option<u64>,
+    /// Block every read was pinned to. `None` for blocked wallets, which skip the chain, and for
+    /// multicall batches, which read everything in one call anyway.
+    pub block_number: Option<u64>,
 }
 
 impl StakeService {
     pub fn new(config: &Config) -> Self {
         Self {
-            client: Client::new(),
-            rpc_url: config.base_rpc_url.clone(),
+            client: outbound_client(config),
+            rpc_urls: config.base_rpc_urls.clone(),
             contract_address: config.staking_contract_address.clone(),
+            token_address: config.stake_token_address.clone(),
+            multicall3_address: config.multicall3_address.clone(),
             blocked_unlink_wallets: config.blocked_unlink_wallets.clone(),
+            lock_mode: config.stake_lock_mode,
+            position_mode: config.stake_position_mode,
+            cache_ttl: (config.stake_status_cache_ttl_secs > 0)
+                .then(|| Duration::from_secs(config.stake_status_cache_ttl_secs)),
+            cache: Arc::default(),
         }
     }
 
+    /// Stake status, served from the `STAKE_STATUS_CACHE_TTL_SECS` cache when a read of the same
+    /// wallet is recent enough. Blocked wallets never enter the cache.
     pub async fn stake_status(&self, wallet_address: &str) -> ApiResult<StakeStatus> {
-        if self
-            .blocked_unlink_wallets
-            .iter()
-            .any(|w| w.eq_ignore_ascii_case(wallet_address))
+        let Some(ttl) = self.cache_ttl else {
+            return self.stake_status_uncached(wallet_address).await;
+        };
+        if let Some(status) = self.blocked_wallet_status(wallet_address) {
+            return Ok(status);
+        }
+
+        let key = wallet_address.trim().to_lowercase();
+        if let Some((read_at, status)) = self.cache.lock().expect("stake cache lock").get(&key)
+            && read_at.elapsed() < ttl
         {
+            return Ok(status.clone());
+        }
+
+        let status = self.stake_status_uncached(wallet_address).await?;
+        let mut cache = self.cache.lock().expect("stake cache lock");
+        cache.retain(|_, (read_at, _)| read_at.elapsed() < ttl);
+        cache.insert(key, (Instant::now(), status.clone()));
+        Ok(status)
+    }
+
+    /// Always reads the chain; used where a decision must not rest on a cached read. Every read
+    /// is pinned to the block current when the lookup starts, so a withdrawal landing between
+    /// the balance and unlock time reads cannot pair one state's balance with another's lock.
+    pub async fn stake_status_uncached(&self, wallet_address: &str) -> ApiResult<StakeStatus> {
+        if let Some(status) = self.blocked_wallet_status(wallet_address) {
+            return Ok(status);
+        }
+        let block_number = self.block_number().await?;
+        let block = format!("{block_number:#x}");
+        if self.position_mode == StakePositionMode::Positions {
+            let status = self.position_stake_status(wallet_address, &block).await?;
             return Ok(StakeStatus {
-                balance_wei: 1,
-                unlock_time_unix: u64::MAX,
+                block_number: Some(block_number),
+                ..status
             });
         }
 
-        let balance_hex = self
-            .eth_call_address_u256("stakedBalance(address)", wallet_address)
-            .await?;
-        let unlock_hex = self
-            .eth_call_address_u256("unlockTime(address)", wallet_address)
-            .await?;
+        let (balance_target, balance_signature) = self.balance_source()?;
+        let balance_data = encode_call_data(balance_signature, wallet_address)?;
+        let balance_hex = self.eth_call(balance_target, &balance_data, &block).await?;
+        let unlock_time_unix = match self.lock_mode {
+            StakeLockMode::Required => {
+                let unlock_hex = self
+                    .eth_call_address_u256("unlockTime(address)", wallet_address, &block)
+                    .await?;
+                Some(parse_u256_hex_to_u64(&unlock_hex)?)
+            }
+            StakeLockMode::Disabled => None,
+        };
 
         Ok(StakeStatus {
             balance_wei: parse_u256_hex_to_u128(&balance_hex)?,
-            unlock_time_unix: parse_u256_hex_to_u64(&unlock_hex)?,
+            unlock_time_unix,
+            block_number: Some(block_number),
         })
     }
 
+    /// Reads every lock position of the wallet one after another; see `sum_stake_positions`.
+    async fn position_stake_status(
+        &self,
+        wallet_address: &str,
+        block: &str,
+    ) -> ApiResult<StakeStatus> {
+        let count_hex = self
+            .eth_call_address_u256("stakeCount(address)", wallet_address, block)
+            .await?;
+        let count = parse_u256_hex_to_u128(&count_hex)?;
+        if count > MAX_STAKE_POSITIONS {
+            return Err(ApiError::validation(format!(
+                "wallet has more than {MAX_STAKE_POSITIONS} stake positions"
+            )));
+        }
+
+        let contract = self.contract_address()?;
+        let mut positions = Vec::with_capacity(count as usize);
+        for index in 0..count {
+            let data = encode_position_call(wallet_address, index)?;
+            let result = self.eth_call(contract, &data, block).await?;
+            positions.push(decode_stake_position(&result)?);
+        }
+        sum_stake_positions(&positions, self.lock_mode == StakeLockMode::Required)
+    }
+
+    /// Blocked wallets report a sentinel stake without touching the RPC.
+    fn blocked_wallet_status(&self, wallet_address: &str) -> Option<StakeStatus> {
+        self.blocked_unlink_wallets
+            .iter()
+            .any(|w| w.eq_ignore_ascii_case(wallet_address))
+            .then_some(StakeStatus {
+                balance_wei: 1,
+                unlock_time_unix: Some(u64::MAX),
+                block_number: None,
+            })
+    }
+
+    /// Looks up stake status for each wallet. With `MULTICALL3_ADDRESS` set every read is folded
+    /// into one Multicall3 `aggregate3` `eth_call`; otherwise each wallet is looked up separately,
+    /// keeping at most `STAKE_BATCH_CONCURRENCY` lookups in flight. Results are returned in input
+    /// order.
+    pub async fn stake_status_batch(
+        &self,
+        wallet_addresses: &[String],
+    ) -> ApiResult<Vec<StakeStatus>> {
+        // Position counts are only known after a first read, so positions are never multicalled.
+        if let Some(multicall3_address) = &self.multicall3_address
+            && self.position_mode == StakePositionMode::Single
+        {
+            return self
+                .stake_status_multicall(multicall3_address, wallet_addresses)
+                .await;
+        }
+
+        let semaphore = Arc::new(Semaphore::new(STAKE_BATCH_CONCURRENCY));
+        let mut tasks = JoinSet::new();
+        for (index, wallet_address) in wallet_addresses.iter().enumerate() {
+            let service = self.clone();
+            let semaphore = semaphore.clone();
+            let wallet_address = wallet_address.clone();
+            tasks.spawn(async move {
+                let _permit = semaphore
+                    .acquire_owned()
+                    .await
+                    .map_err(|e| ApiError::Internal(e.into()))?;
+                let status = service.stake_status(&wallet_address).await?;
+                Ok::<_, ApiError>((index, status))
+            });
+        }
+
+        let mut statuses = vec![None; wallet_addresses.len()];
+        while let Some(joined) = tasks.join_next().await {
+            let (index, status) = joined.map_err(|e| ApiError::Internal(e.into()))??;
+            statuses[index] = Some(status);
+        }
+        Ok(statuses.into_iter().flatten().collect())
+    }
+
+    async fn stake_status_multicall(
+        &self,
+        multicall3_address: &str,
+        wallet_addresses: &[String],
+    ) -> ApiResult<Vec<StakeStatus>> {
+        let pending: Vec<&str> = wallet_addresses
+            .iter()
+            .map(String::as_str)
+            .filter(|wallet| self.blocked_wallet_status(wallet).is_none())
+            .collect();
+        let mut fetched = if pending.is_empty() {
+            Vec::new()
+        } else {
+            let contract = self.contract_address()?;
+            let with_unlock_time = self.lock_mode == StakeLockMode::Required;
+            let data = encode_stake_multicall(
+                self.balance_source()?,
+                contract,
+                &pending,
+                with_unlock_time,
+            )?;
+            let result = self.eth_call(multicall3_address, &data, "latest").await?;
+            decode_stake_multicall(&result, pending.len(), with_unlock_time)?
+        }
+        .into_iter();
+
+        wallet_addresses
+            .iter()
+            .map(|wallet| match self.blocked_wallet_status(wallet) {
+                Some(status) => Ok(status),
+                None => fetched.next().ok_or_else(|| {
+                    ApiError::validation("stake multicall returned too few results")
+                }),
+            })
+            .collect()
+    }
+
+    /// Contract and function the stake balance is read from. The unlock time always comes from
+    /// the staking contract.
+    fn balance_source(&self) -> ApiResult<(&str, &'static str)> {
+        match &self.token_address {
+            Some(token_address) => Ok((token_address, "balanceOf(address)")),
+            None => Ok((self.contract_address()?, "stakedBalance(address)")),
+        }
+    }
+
+    fn contract_address(&self) -> ApiResult<&str> {
+        self.contract_address
+            .as_deref()
+            .ok_or_else(|| ApiError::validation("STAKING_CONTRACT_ADDRESS is not configured"))
+    }
+
+    /// `block` is a block tag (`latest`) or a `0x`-prefixed block number.
     async fn eth_call_address_u256(
         &self,
         function_sig: &str,
         wallet_address: &str,
+        block: &str,
     ) -> ApiResult<String> {
-        let rpc_url = self
-            .rpc_url
-            .as_ref()
-            .ok_or_else(|| ApiError::validation("BASE_RPC_URL is not configured"))?;
-        let contract = self
-            .contract_address
-            .as_ref()
-            .ok_or_else(|| ApiError::validation("STAKING_CONTRACT_ADDRESS is not configured"))?;
-
+        let contract = self.contract_address()?;
         let data = encode_call_data(function_sig, wallet_address)?;
+        self.eth_call(contract, &data, block).await
+    }
+
+    async fn block_number(&self) -> ApiResult<u64> {
+        let result = self.rpc_request("eth_blockNumber", json!([])).await?;
+        parse_u256_hex_to_u64(&result)
+    }
+
+    async fn eth_call(&self, contract: &str, data: &str, block: &str) -> ApiResult<String> {
+        self.rpc_request(
+            "eth_call",
+            json!([
+              {
+                "to": contract,
+                "data": data
+              },
+              block
+            ]),
+        )
+        .await
+    }
+
+    /// Tries each RPC endpoint in order, moving on after a network error or non-2xx response,
+    /// for up to `RPC_MAX_ROUNDS` jittered rounds. A 2xx response is final even if it carries a
+    /// JSON-RPC error, since every endpoint would answer the same call the same way.
+    async fn rpc_request(&self, method: &str, params: serde_json::Value) -> ApiResult<String> {
+        if self.rpc_urls.is_empty() {
+            return Err(ApiError::validation("BASE_RPC_URL is not configured"));
+        }
+
         let body = json!({
           "jsonrpc": "2.0",
           "id": 1,
-          "method": "eth_call",
-          "params": [
-            {
-              "to": contract,
-              "data": data
-            },
-            "latest"
-          ]
+          "method": method,
+          "params": params
         });
 
-        let response = self
-            .client
-            .post(rpc_url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| ApiError::Internal(e.into()))?;
-
-        if !response.status().is_success() {
-            return Err(ApiError::validation("stake RPC call failed"));
+        let mut last_err = ApiError::validation("stake RPC call failed");
+        for round in 1..=RPC_MAX_ROUNDS {
+            if round > 1 {
+                let jitter_ms =
+                    rand::thread_rng().gen_range(0..=RPC_ROUND_BACKOFF.as_millis() as u64);
+                tokio::time::sleep(RPC_ROUND_BACKOFF + Duration::from_millis(jitter_ms)).await;
+            }
+            for rpc_url in &self.rpc_urls {
+                match self.client.post(rpc_url).json(&body).send().await {
+                    Ok(response) if response.status().is_success() => {
+                        let payload: RpcResponse = response
+                            .json()
+                            .await
+                            .map_err(|e| ApiError::Internal(e.into()))?;
+                        return Ok(payload.result);
+                    }
+                    Ok(response) => {
+                        tracing::warn!(rpc_url, round, status = %response.status(), "stake RPC endpoint failed");
+                        last_err = ApiError::validation("stake RPC call failed");
+                    }
+                    Err(err) => {
+                        tracing::warn!(rpc_url, round, error = %err, "stake RPC endpoint unreachable");
+                        last_err = ApiError::Internal(err.into());
+                    }
+                }
+            }
         }
-
-        let payload: EthCallResponse = response
-            .json()
-            .await
-            .map_err(|e| ApiError::Internal(e.into()))?;
-
-        Ok(payload.result)
+        Err(last_err)
     }
 }
 
@@ -138,6 +385,143 @@ fn encode_call_data(function_sig: &str, wallet_address: &str) -> ApiResult<Strin
     Ok(format!("0x{}{}", hex::encode(&selector[0..4]), padded))
 }
 
+fn encode_position_call(wallet_address: &str, index: u128) -> ApiResult<String> {
+    let call_data = encode_call_data("stakeAt(address,uint256)", wallet_address)?;
+    Ok(format!("{call_data}{index:064x}"))
+}
+
+fn decode_stake_position(result_hex: &str) -> ApiResult<StakePosition> {
+    let raw = result_hex.trim_start_matches("0x");
+    if raw.len() < 128 {
+        return Err(ApiError::validation("invalid stake position response"));
+    }
+    Ok(StakePosition {
+        amount_wei: parse_u256_hex_to_u128(&raw[0..64])?,
+        unlock_time_unix: parse_u256_hex_to_u64(&raw[64..128])?,
+    })
+}
+
+/// Sums every position; the lock is the earliest unlock among non-empty positions, so stake only
+/// counts as locked while all of it is. A wallet with no non-empty position reports unlock time 0.
+fn sum_stake_positions(
+    positions: &[StakePosition],
+    with_unlock_time: bool,
+) -> ApiResult<StakeStatus> {
+    let balance_wei = positions.iter().try_fold(0u128, |total, position| {
+        total.checked_add(position.amount_wei).ok_or_else(|| {
+            ApiError::validation("value too large for u128; unsupported in MVP backend")
+        })
+    })?;
+    let unlock_time_unix = with_unlock_time.then(|| {
+        positions
+            .iter()
+            .filter(|position| position.amount_wei > 0)
+            .map(|position| position.unlock_time_unix)
+            .min()
+            .unwrap_or(0)
+    });
+    Ok(StakeStatus {
+        balance_wei,
+        unlock_time_unix,
+        block_number: None,
+    })
+}
+
+fn parse_address(raw: &str, what: &str) -> ApiResult<Address> {
+    Address::from_str(raw.trim())
+        .map_err(|_| ApiError::validation(format!("{what} must be 20-byte hex with 0x prefix")))
+}
+
+/// Encodes `aggregate3` over the balance read (`stakedBalance(wallet)`, or the token's
+/// `balanceOf(wallet)`) and `unlockTime(wallet)` when `with_unlock_time` for each wallet, in
+/// wallet order. `allowFailure` is false, so one reverting
+/// read fails the whole call rather than leaving a wallet half-read.
+fn encode_stake_multicall(
+    (balance_contract, balance_signature): (&str, &str),
+    contract: &str,
+    wallet_addresses: &[&str],
+    with_unlock_time: bool,
+) -> ApiResult<String> {
+    let target = parse_address(contract, "STAKING_CONTRACT_ADDRESS")?;
+    // When the balance is not read from the staking contract it comes from the stake token.
+    let balance_target = parse_address(balance_contract, "STAKE_TOKEN_ADDRESS")?;
+    let mut calls = Vec::new();
+    for wallet in wallet_addresses {
+        let mut reads = vec![(balance_target, balance_signature)];
+        if with_unlock_time {
+            reads.push((target, "unlockTime(address)"));
+        }
+        for (target, signature) in reads {
+            let call_data = encode_call_data(signature, wallet)?;
+            let call_data = hex::decode(call_data.trim_start_matches("0x"))
+                .map_err(|e| ApiError::Internal(e.into()))?;
+            calls.push(Token::Tuple(vec![
+                Token::Address(target),
+                Token::Bool(false),
+                Token::Bytes(call_data),
+            ]));
+        }
+    }
+
+    let selector = Keccak256::digest(AGGREGATE3_SIGNATURE.as_bytes());
+    let encoded = abi::encode(&[Token::Array(calls)]);
+    Ok(format!(
+        "0x{}{}",
+        hex::encode(&selector[0..4]),
+        hex::encode(encoded)
+    ))
+}
+
+/// Decodes `aggregate3`'s `(bool success, bytes returnData)[]` back into one status per wallet.
+fn decode_stake_multicall(
+    result_hex: &str,
+    wallet_count: usize,
+    with_unlock_time: bool,
+) -> ApiResult<Vec<StakeStatus>> {
+    let invalid = || ApiError::validation("invalid stake multicall response");
+    let raw = hex::decode(result_hex.trim_start_matches("0x")).map_err(|_| invalid())?;
+    let result_type = ParamType::Array(Box::new(ParamType::Tuple(vec![
+        ParamType::Bool,
+        ParamType::Bytes,
+    ])));
+    let Some(Token::Array(results)) = abi::decode(&[result_type], &raw)
+        .map_err(|_| invalid())?
+        .into_iter()
+        .next()
+    else {
+        return Err(invalid());
+    };
+
+    let calls_per_wallet = if with_unlock_time { 2 } else { 1 };
+    if results.len() != wallet_count * calls_per_wallet {
+        return Err(invalid());
+    }
+    let words = results
+        .into_iter()
+        .map(|result| match result {
+            Token::Tuple(fields) => match fields.as_slice() {
+                [Token::Bool(true), Token::Bytes(data)] => Ok(format!("0x{}", hex::encode(data))),
+                _ => Err(ApiError::validation("stake RPC call failed")),
+            },
+            _ => Err(invalid()),
+        })
+        .collect::<ApiResult<Vec<_>>>()?;
+
+    words
+        .chunks(calls_per_wallet)
+        .map(|chunk| {
+            Ok(StakeStatus {
+                balance_wei: parse_u256_hex_to_u128(&chunk[0])?,
+                unlock_time_unix: chunk
+                    .get(1)
+                    .map(|unlock_hex| parse_u256_hex_to_u64(unlock_hex))
+                    .transpose()?,
+                block_number: None,
+            })
+        })
+        .collect()
+}
+
 fn parse_u256_hex_to_u128(hex_value: &str) -> ApiResult<u128> {
     let raw = hex_value.trim_start_matches("0x");
     u128::from_str_radix(raw, 16)
@@ -156,18 +540,8 @@ mod tests {
 
     fn test_config(blocked_unlink_wallets: Vec<String>) -> Config {
         Config {
-            host: "0.0.0.0".to_string(),
-            port: 8080,
-            database_url: "postgres://localhost/sitg".to_string(),
-            db_max_connections: 10,
-            app_base_url: "https://sitg.io".to_string(),
-            api_base_url: "http://localhost:8080".to_string(),
-            github_client_id: None,
-            github_client_secret: None,
-            session_cookie_name: "sitg_session".to_string(),
             blocked_unlink_wallets,
-            base_rpc_url: None,
-            staking_contract_address: None,
+            ..Config::for_tests()
         }
     }
 
@@ -219,6 +593,663 @@ mod tests {
             .await
             .expect("blocked wallet should short-circuit");
         assert_eq!(status.balance_wei, 1);
-        assert_eq!(status.unlock_time_unix, u64::MAX);
+        assert_eq!(status.unlock_time_unix, Some(u64::MAX));
+    }
+
+    /// JSON-RPC stub whose `eth_call` result is the last byte of the queried wallet address.
+    async fn spawn_echo_rpc() -> String {
+        use axum::{Json, Router, routing::post};
+        use serde_json::Value;
+
+        let app = Router::new().route(
+            "/",
+            post(|Json(body): Json<Value>| async move {
+                if body["method"] == "eth_blockNumber" {
+                    return Json(json!({"jsonrpc": "2.0", "id": 1, "result": "0x1"}));
+                }
+                let data = body["params"][0]["data"].as_str().unwrap_or_default();
+                let last_byte = &data[data.len() - 2..];
+                Json(json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{last_byte}")}))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind rpc stub");
+        let addr = listener.local_addr().expect("rpc stub addr");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve rpc stub");
+        });
+        format!("http://{addr}/")
+    }
+
+    #[tokio::test]
+    async fn batch_lookup_preserves_input_order() {
+        let service = StakeService::new(&Config {
+            base_rpc_urls: vec![spawn_echo_rpc().await],
+            staking_contract_address: Some(
+                "0x2222222222222222222222222222222222222222".to_string(),
+            ),
+            ..Config::for_tests()
+        });
+        let wallets: Vec<String> = (1..=20u8)
+            .map(|i| format!("0x{}{:02x}", "0".repeat(38), i))
+            .collect();
+
+        let statuses = service
+            .stake_status_batch(&wallets)
+            .await
+            .expect("batch lookup");
+
+        let balances: Vec<u128> = statuses.iter().map(|s| s.balance_wei).collect();
+        assert_eq!(balances, (1..=20u128).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn disabled_lock_mode_reads_only_the_balance() {
+        let service = StakeService::new(&Config {
+            base_rpc_urls: vec![spawn_echo_rpc().await],
+            staking_contract_address: Some(
+                "0x2222222222222222222222222222222222222222".to_string(),
+            ),
+            stake_lock_mode: StakeLockMode::Disabled,
+            ..Config::for_tests()
+        });
+
+        let status = service
+            .stake_status("0x0000000000000000000000000000000000000007")
+            .await
+            .expect("balance only");
+        assert_eq!(status.balance_wei, 7);
+        assert_eq!(status.unlock_time_unix, None);
+    }
+    fn word(value: u128) -> String {
+        format!("{value:064x}")
+    }
+
+    /// `aggregate3` result for one wallet: `[(true, 2 ETH), (true, 1_700_000_000)]`.
+    fn two_result_response() -> String {
+        [
+            word(0x20),
+            word(2),
+            word(0x40),
+            word(0xc0),
+            word(1),
+            word(0x40),
+            word(0x20),
+            word(2_000_000_000_000_000_000),
+            word(1),
+            word(0x40),
+            word(0x20),
+            word(1_700_000_000),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn encodes_aggregate3_call_for_balance_reads() {
+        let contract = "0x2222222222222222222222222222222222222222";
+        let data = encode_stake_multicall(
+            (contract, "stakedBalance(address)"),
+            contract,
+            &["0x1111111111111111111111111111111111111111"],
+            false,
+        )
+        .expect("encode");
+
+        let expected = [
+            "0x82ad56cb".to_string(),
+            word(0x20),
+            word(1),
+            word(0x20),
+            format!("{:0>64}", "2222222222222222222222222222222222222222"),
+            word(0),
+            word(0x60),
+            word(0x24),
+            format!(
+                "60217267{:0>64}{}",
+                "1111111111111111111111111111111111111111",
+                "0".repeat(56)
+            ),
+        ]
+        .concat();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn encodes_balance_then_unlock_time_per_wallet() {
+        let contract = "0x2222222222222222222222222222222222222222";
+        let data = encode_stake_multicall(
+            (contract, "stakedBalance(address)"),
+            contract,
+            &[
+                "0x1111111111111111111111111111111111111111",
+                "0x3333333333333333333333333333333333333333",
+            ],
+            true,
+        )
+        .expect("encode");
+        let raw = hex::decode(&data[2 + 8..]).expect("hex");
+        let call_type = ParamType::Array(Box::new(ParamType::Tuple(vec![
+            ParamType::Address,
+            ParamType::Bool,
+            ParamType::Bytes,
+        ])));
+        let Some(Token::Array(calls)) = abi::decode(&[call_type], &raw)
+            .expect("decode")
+            .into_iter()
+            .next()
+        else {
+            panic!("expected a call array");
+        };
+
+        let call_data: Vec<String> = calls
+            .into_iter()
+            .map(|call| match call {
+                Token::Tuple(fields) => match fields.as_slice() {
+                    [Token::Address(_), Token::Bool(false), Token::Bytes(data)] => {
+                        hex::encode(&data[0..4]) + &hex::encode(&data[35..36])
+                    }
+                    other => panic!("unexpected call {other:?}"),
+                },
+                other => panic!("unexpected token {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            call_data,
+            ["6021726711", "76b467b711", "6021726733", "76b467b733"]
+        );
+
+        let err =
+            encode_stake_multicall(("0x2222", "stakedBalance(address)"), "0x2222", &[], false)
+                .expect_err("bad contract");
+        assert!(matches!(err, ApiError::Validation(_)));
+    }
+
+    #[test]
+    fn decodes_aggregate3_results_into_statuses() {
+        let statuses = decode_stake_multicall(&format!("0x{}", two_result_response()), 1, true)
+            .expect("decode");
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].balance_wei, 2_000_000_000_000_000_000);
+        assert_eq!(statuses[0].unlock_time_unix, Some(1_700_000_000));
+
+        // Lock-less contracts read only balances: two results are two wallets.
+        let statuses = decode_stake_multicall(&format!("0x{}", two_result_response()), 2, false)
+            .expect("decode");
+        let balances: Vec<u128> = statuses.iter().map(|s| s.balance_wei).collect();
+        assert_eq!(balances, [2_000_000_000_000_000_000, 1_700_000_000]);
+        assert!(statuses.iter().all(|s| s.unlock_time_unix.is_none()));
+
+        let err = decode_stake_multicall(&format!("0x{}", two_result_response()), 2, true)
+            .expect_err("result count mismatch");
+        assert!(matches!(err, ApiError::Validation(_)));
+
+        let failed = two_result_response().replacen(
+            &format!("{}{}", word(1), word(0x40)),
+            &format!("{}{}", word(0), word(0x40)),
+            1,
+        );
+        let err = decode_stake_multicall(&format!("0x{failed}"), 1, true).expect_err("failed call");
+        assert!(matches!(err, ApiError::Validation(_)));
+
+        let err = decode_stake_multicall("0xdeadbeef", 1, true).expect_err("garbage");
+        assert!(matches!(err, ApiError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn batch_lookup_uses_one_multicall_and_skips_blocked_wallets() {
+        use axum::{Json, Router, routing::post};
+        use serde_json::Value;
+        use std::sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        };
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&calls);
+        let app = Router::new().route(
+            "/",
+            post(move |Json(body): Json<Value>| {
+                let seen = Arc::clone(&seen);
+                async move {
+                    seen.fetch_add(1, Ordering::SeqCst);
+                    assert_eq!(
+                        body["params"][0]["to"],
+                        "0xca11bde05977b3631167028862be2a173976ca11"
+                    );
+                    Json(json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{}", two_result_response())}))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind rpc stub");
+        let addr = listener.local_addr().expect("rpc stub addr");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve rpc stub");
+        });
+
+        let blocked = "0x1111111111111111111111111111111111111111".to_string();
+        let service = StakeService::new(&Config {
+            base_rpc_urls: vec![format!("http://{addr}/")],
+            staking_contract_address: Some(
+                "0x2222222222222222222222222222222222222222".to_string(),
+            ),
+            multicall3_address: Some("0xca11bde05977b3631167028862be2a173976ca11".to_string()),
+            ..test_config(vec![blocked.clone()])
+        });
+
+        let statuses = service
+            .stake_status_batch(&[
+                blocked,
+                "0x3333333333333333333333333333333333333333".to_string(),
+            ])
+            .await
+            .expect("multicall batch");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(statuses[0].unlock_time_unix, Some(u64::MAX));
+        assert_eq!(statuses[1].balance_wei, 2_000_000_000_000_000_000);
+        assert_eq!(statuses[1].unlock_time_unix, Some(1_700_000_000));
+    }
+
+    #[tokio::test]
+    async fn caches_stake_status_within_ttl() {
+        use axum::{Json, Router, routing::post};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&calls);
+        let app = Router::new().route(
+            "/",
+            post(move || {
+                let seen = Arc::clone(&seen);
+                async move {
+                    seen.fetch_add(1, Ordering::SeqCst);
+                    Json(json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{:064x}", 5)}))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind rpc stub");
+        let addr = listener.local_addr().expect("rpc stub addr");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve rpc stub");
+        });
+
+        let blocked = "0x1111111111111111111111111111111111111111".to_string();
+        let service = StakeService::new(&Config {
+            base_rpc_urls: vec![format!("http://{addr}/")],
+            staking_contract_address: Some(
+                "0x2222222222222222222222222222222222222222".to_string(),
+            ),
+            stake_status_cache_ttl_secs: 60,
+            ..test_config(vec![blocked.clone()])
+        });
+        let wallet = "0x00000000000000000000000000000000000000aB";
+
+        let first = service.stake_status(wallet).await.expect("first read");
+        assert_eq!(first.balance_wei, 5);
+        assert_eq!(
+            calls.load(Ordering::SeqCst),
+            3,
+            "block number, balance and unlock time"
+        );
+
+        let second = service
+            .stake_status(&wallet.to_uppercase().replace("0X", "0x"))
+            .await
+            .expect("cached read");
+        assert_eq!(second.balance_wei, 5);
+        assert_eq!(
+            calls.load(Ordering::SeqCst),
+            3,
+            "second read within ttl is cached"
+        );
+
+        service
+            .stake_status_uncached(wallet)
+            .await
+            .expect("uncached read");
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+
+        service
+            .stake_status(&blocked)
+            .await
+            .expect("blocked wallet");
+        assert_eq!(calls.load(Ordering::SeqCst), 6);
+        assert!(
+            !service
+                .cache
+                .lock()
+                .expect("lock")
+                .contains_key(&blocked.to_lowercase())
+        );
+
+        service
+            .cache
+            .lock()
+            .expect("lock")
+            .get_mut(&wallet.to_lowercase())
+            .expect("cached")
+            .0 = Instant::now() - Duration::from_secs(61);
+        service.stake_status(wallet).await.expect("expired read");
+        assert_eq!(
+            calls.load(Ordering::SeqCst),
+            9,
+            "expired entries are re-read"
+        );
+    }
+
+    #[test]
+    fn decodes_and_sums_stake_positions() {
+        let response =
+            |amount: u128, unlock: u64| format!("0x{}{}", word(amount), word(unlock.into()));
+        let positions = [
+            decode_stake_position(&response(1_000, 1_800_000_000)).expect("position"),
+            decode_stake_position(&response(0, 1_600_000_000)).expect("position"),
+            decode_stake_position(&response(2_500, 1_750_000_000)).expect("position"),
+        ];
+        assert_eq!(
+            positions[2],
+            StakePosition {
+                amount_wei: 2_500,
+                unlock_time_unix: 1_750_000_000,
+            }
+        );
+
+        let status = sum_stake_positions(&positions, true).expect("sum");
+        assert_eq!(status.balance_wei, 3_500);
+        assert_eq!(
+            status.unlock_time_unix,
+            Some(1_750_000_000),
+            "the emptied position does not count"
+        );
+        assert_eq!(
+            sum_stake_positions(&positions, false)
+                .expect("sum")
+                .unlock_time_unix,
+            None
+        );
+        assert_eq!(
+            sum_stake_positions(&[], true)
+                .expect("sum")
+                .unlock_time_unix,
+            Some(0)
+        );
+
+        let err = decode_stake_position(&format!("0x{}", word(1))).expect_err("one word");
+        assert!(matches!(err, ApiError::Validation(_)));
+        let huge = StakePosition {
+            amount_wei: u128::MAX,
+            unlock_time_unix: 0,
+        };
+        let err = sum_stake_positions(&[huge, huge], true).expect_err("overflow");
+        assert!(matches!(err, ApiError::Validation(_)));
+
+        assert_eq!(
+            encode_position_call("0x1111111111111111111111111111111111111111", 2).expect("encode"),
+            format!(
+                "0x{}{:0>64}{}",
+                hex::encode(&Keccak256::digest(b"stakeAt(address,uint256)")[0..4]),
+                "1111111111111111111111111111111111111111",
+                word(2)
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn positions_mode_enumerates_and_sums_every_position() {
+        use axum::{Json, Router, routing::post};
+        use serde_json::Value;
+
+        let count_selector = hex::encode(&Keccak256::digest(b"stakeCount(address)")[0..4]);
+        let app = Router::new().route(
+            "/",
+            post(move |Json(body): Json<Value>| {
+                let count_selector = count_selector.clone();
+                async move {
+                    let data = body["params"][0]["data"].as_str().unwrap_or_default();
+                    let result = if body["method"] == "eth_blockNumber" {
+                        word(1)
+                    } else if data[2..].starts_with(&count_selector) {
+                        word(3)
+                    } else {
+                        // stakeAt(wallet, i): position i holds (i + 1) * 100 until 1_800_000_000 + i.
+                        let index =
+                            u128::from_str_radix(&data[data.len() - 64..], 16).expect("index");
+                        format!("{}{}", word((index + 1) * 100), word(1_800_000_000 + index))
+                    };
+                    Json(json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{result}")}))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind rpc stub");
+        let addr = listener.local_addr().expect("rpc stub addr");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve rpc stub");
+        });
+
+        let service = StakeService::new(&Config {
+            base_rpc_urls: vec![format!("http://{addr}/")],
+            staking_contract_address: Some(
+                "0x2222222222222222222222222222222222222222".to_string(),
+            ),
+            stake_position_mode: StakePositionMode::Positions,
+            ..Config::for_tests()
+        });
+
+        let status = service
+            .stake_status("0x3333333333333333333333333333333333333333")
+            .await
+            .expect("positions");
+        assert_eq!(status.balance_wei, 600);
+        assert_eq!(status.unlock_time_unix, Some(1_800_000_000));
+    }
+
+    #[tokio::test]
+    async fn pins_balance_and_unlock_time_reads_to_one_block() {
+        use axum::{Json, Router, routing::post};
+        use serde_json::Value;
+
+        let blocks = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&blocks);
+        let app = Router::new().route(
+            "/",
+            post(move |Json(body): Json<Value>| {
+                let seen = Arc::clone(&seen);
+                async move {
+                    let result = if body["method"] == "eth_blockNumber" {
+                        "0x12d687".to_string()
+                    } else {
+                        seen.lock()
+                            .expect("blocks lock")
+                            .push(body["params"][1].clone());
+                        format!("0x{}", word(9))
+                    };
+                    Json(json!({"jsonrpc": "2.0", "id": 1, "result": result}))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind rpc stub");
+        let addr = listener.local_addr().expect("rpc stub addr");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve rpc stub");
+        });
+
+        let service = StakeService::new(&Config {
+            base_rpc_urls: vec![format!("http://{addr}/")],
+            staking_contract_address: Some(
+                "0x2222222222222222222222222222222222222222".to_string(),
+            ),
+            ..Config::for_tests()
+        });
+
+        let status = service
+            .stake_status("0x3333333333333333333333333333333333333333")
+            .await
+            .expect("pinned read");
+        assert_eq!(status.block_number, Some(1_234_567));
+        assert_eq!(
+            *blocks.lock().expect("blocks lock"),
+            vec![json!("0x12d687"), json!("0x12d687")],
+            "balance and unlock time read at the same block"
+        );
+    }
+
+    #[tokio::test]
+    async fn token_mode_reads_balance_of_from_the_stake_token() {
+        use axum::{Json, Router, routing::post};
+        use serde_json::Value;
+
+        let token = "0x4444444444444444444444444444444444444444";
+        let balance_of_selector = hex::encode(&Keccak256::digest(b"balanceOf(address)")[0..4]);
+        let unlock_selector = hex::encode(&Keccak256::digest(b"unlockTime(address)")[0..4]);
+        let app = Router::new().route(
+            "/",
+            post(move |Json(body): Json<Value>| {
+                let balance_of_selector = balance_of_selector.clone();
+                let unlock_selector = unlock_selector.clone();
+                async move {
+                    let to = body["params"][0]["to"].as_str().unwrap_or_default();
+                    let data = body["params"][0]["data"].as_str().unwrap_or_default();
+                    let result = if body["method"] == "eth_blockNumber" {
+                        word(1)
+                    } else if to == token && data[2..].starts_with(&balance_of_selector) {
+                        // 2.5 tokens of a 6-decimal token.
+                        word(2_500_000)
+                    } else if to != token && data[2..].starts_with(&unlock_selector) {
+                        word(1_800_000_000)
+                    } else {
+                        panic!("unexpected call to {to}: {data}")
+                    };
+                    Json(json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{result}")}))
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind rpc stub");
+        let addr = listener.local_addr().expect("rpc stub addr");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve rpc stub");
+        });
+
+        let service = StakeService::new(&Config {
+            base_rpc_urls: vec![format!("http://{addr}/")],
+            staking_contract_address: Some(
+                "0x2222222222222222222222222222222222222222".to_string(),
+            ),
+            stake_token_address: Some(token.to_string()),
+            stake_token_decimals: 6,
+            ..Config::for_tests()
+        });
+
+        let status = service
+            .stake_status("0x3333333333333333333333333333333333333333")
+            .await
+            .expect("token balance");
+        assert_eq!(status.balance_wei, 2_500_000);
+        assert_eq!(status.unlock_time_unix, Some(1_800_000_000));
+    }
+
+    /// RPC stub answering every call with `status` and a `value` result, counting requests.
+    async fn spawn_counting_rpc(
+        status: u16,
+        value: u128,
+    ) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use axum::{Json, Router, http::StatusCode, routing::post};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&hits);
+        let app = Router::new().route(
+            "/",
+            post(move || {
+                let seen = Arc::clone(&seen);
+                async move {
+                    seen.fetch_add(1, Ordering::SeqCst);
+                    (
+                        StatusCode::from_u16(status).expect("status"),
+                        Json(json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{}", word(value))})),
+                    )
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind rpc stub");
+        let addr = listener.local_addr().expect("rpc stub addr");
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("serve rpc stub");
+        });
+        (format!("http://{addr}/"), hits)
+    }
+
+    #[tokio::test]
+    async fn fails_over_to_the_next_rpc_endpoint() {
+        use std::sync::atomic::Ordering;
+
+        let (primary, primary_hits) = spawn_counting_rpc(503, 0).await;
+        let (backup, backup_hits) = spawn_counting_rpc(200, 42).await;
+        let service = StakeService::new(&Config {
+            base_rpc_urls: vec![primary, backup],
+            staking_contract_address: Some(
+                "0x2222222222222222222222222222222222222222".to_string(),
+            ),
+            ..Config::for_tests()
+        });
+
+        let status = service
+            .stake_status("0x3333333333333333333333333333333333333333")
+            .await
+            .expect("backup endpoint answers");
+        assert_eq!(status.balance_wei, 42);
+        assert_eq!(status.unlock_time_unix, Some(42));
+        assert_eq!(
+            primary_hits.load(Ordering::SeqCst),
+            3,
+            "primary tried per call"
+        );
+        assert_eq!(backup_hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_bounded_rounds_when_every_endpoint_fails() {
+        use std::sync::atomic::Ordering;
+
+        let (primary, primary_hits) = spawn_counting_rpc(503, 0).await;
+        let (backup, backup_hits) = spawn_counting_rpc(502, 0).await;
+        let service = StakeService::new(&Config {
+            base_rpc_urls: vec![primary, backup],
+            staking_contract_address: Some(
+                "0x2222222222222222222222222222222222222222".to_string(),
+            ),
+            ..Config::for_tests()
+        });
+
+        let err = service
+            .stake_status("0x3333333333333333333333333333333333333333")
+            .await
+            .expect_err("no endpoint answers");
+        assert!(matches!(err, ApiError::Validation(_)));
+        assert_eq!(primary_hits.load(Ordering::SeqCst), RPC_MAX_ROUNDS as usize);
+        assert_eq!(backup_hits.load(Ordering::SeqCst), RPC_MAX_ROUNDS as usize);
+
+        let unconfigured = StakeService::new(&Config {
+            staking_contract_address: Some(
+                "0x2222222222222222222222222222222222222222".to_string(),
+            ),
+            ..Config::for_tests()
+        });
+        let err = unconfigured
+            .stake_status("0x3333333333333333333333333333333333333333")
+            .await
+            .expect_err("no endpoints");
+        assert!(matches!(err, ApiError::Validation(_)));
     }
 }
@@ -1,39 +1,33 @@
-use sqlx::{Executor, PgPool};
+use sqlx::{
+    PgPool,
+    postgres::{PgConnectOptions, PgPoolOptions},
+};
 use uuid::Uuid;
 
+/// Connects to a fresh schema so every run starts from empty tables, no matter what earlier
+/// runs left behind in `DATABASE_URL`.
 async fn maybe_pool() -> Option<PgPool> {
     let Ok(url) = std::env::var("DATABASE_URL") else {
         return None;
     };
-    PgPool::connect(&url).await.ok()
+    let admin = PgPool::connect(&url).await.ok()?;
+    let schema = format!("test_{}", Uuid::new_v4().simple());
+    sqlx::query(&format!("create schema {schema}"))
+        .execute(&admin)
+        .await
+        .expect("create schema");
+    let options: PgConnectOptions = url.parse().expect("database url");
+    PgPoolOptions::new()
+        .connect_with(options.options([("search_path", schema.as_str())]))
+        .await
+        .ok()
 }
 
 async fn apply_migrations(pool: &PgPool) {
-    pool.execute(include_str!("../migrations/0001_init.sql"))
-        .await
-        .expect("apply 0001");
-    pool.execute(include_str!("../migrations/0002_auth_wallet.sql"))
-        .await
-        .expect("apply 0002");
-    pool.execute(include_str!(
-        "../migrations/0003_internal_replay_and_outbox.sql"
-    ))
-    .await
-    .expect("apply 0003");
-    pool.execute(include_str!("../migrations/0004_bot_action_results.sql"))
-        .await
-        .expect("apply 0004");
-    pool.execute(include_str!("../migrations/0005_bot_tenant_auth.sql"))
-        .await
-        .expect("apply 0005");
-    pool.execute(include_str!(
-        "../migrations/0006_user_sessions_github_access_token.sql"
-    ))
-    .await
-    .expect("apply 0006");
-    pool.execute(include_str!("../migrations/0007_centralized_bot_reset.sql"))
+    sqlx::migrate!("./migrations")
+        .run(pool)
         .await
-        .expect("apply 0007");
+        .expect("apply migrations");
 }
 
 #[tokio::test]
@@ -218,3 +212,108 @@ async fn bot_action_claim_and_result_v2_lifecycle() {
         .expect("get status");
     assert_eq!(status, "DONE");
 }
+
+#[tokio::test]
+#[ignore = "requires DATABASE_URL postgres"]
+async fn wallet_link_nonce_is_globally_unique() {
+    let Some(pool) = maybe_pool().await else {
+        return;
+    };
+    apply_migrations(&pool).await;
+
+    let mut user_ids = Vec::new();
+    for github_user_id in [501_i64, 502] {
+        let user_id: Uuid = sqlx::query_scalar(
+            "insert into users (id, github_user_id, github_login, created_at, updated_at) values ($1, $2, 'u', now(), now()) on conflict (github_user_id) do update set updated_at = now() returning id",
+        )
+        .bind(Uuid::new_v4())
+        .bind(github_user_id)
+        .fetch_one(&pool)
+        .await
+        .expect("user");
+        user_ids.push(user_id);
+    }
+
+    let nonce = Uuid::new_v4();
+    sqlx::query(
+        "insert into wallet_link_challenges (id, user_id, nonce, expires_at, used_at, created_at) values ($1, $2, $3, now() + interval '10 minutes', null, now())",
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_ids[0])
+    .bind(nonce)
+    .execute(&pool)
+    .await
+    .expect("first challenge");
+
+    let duplicate = sqlx::query(
+        "insert into wallet_link_challenges (id, user_id, nonce, expires_at, used_at, created_at) values ($1, $2, $3, now() + interval '10 minutes', null, now())",
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_ids[1])
+    .bind(nonce)
+    .execute(&pool)
+    .await
+    .expect_err("same nonce for another user should fail");
+
+    let constraint = match &duplicate {
+        sqlx::Error::Database(db_err) => db_err.constraint().map(str::to_string),
+        _ => None,
+    };
+    assert_eq!(
+        constraint.as_deref(),
+        Some("wallet_link_challenges_nonce_key")
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires DATABASE_URL postgres"]
+async fn updates_bump_updated_at_without_the_writer_setting_it() {
+    let Some(pool) = maybe_pool().await else {
+        return;
+    };
+    apply_migrations(&pool).await;
+
+    let installation_id = (Uuid::new_v4().as_u128() >> 65) as i64;
+    sqlx::query(
+        "insert into github_installations (installation_id, account_login, account_type, active, created_at) values ($1, 'org', 'Organization', true, now() - interval '1 day')",
+    )
+    .bind(installation_id)
+    .execute(&pool)
+    .await
+    .expect("installation without updated_at uses the default");
+
+    let action_id = Uuid::new_v4();
+    sqlx::query(
+        "insert into bot_actions (id, action_type, challenge_id, installation_id, github_repo_id, repo_full_name, github_pr_number, payload, status, created_at, updated_at) values ($1, 'UPSERT_PR_COMMENT', null, $2, 1, 'org/repo', 1, '{}'::jsonb, 'PENDING', now() - interval '1 day', now() - interval '1 day')",
+    )
+    .bind(action_id)
+    .bind(installation_id)
+    .execute(&pool)
+    .await
+    .expect("action");
+
+    let mut tx = pool.begin().await.expect("begin");
+    let tx_now: chrono::DateTime<chrono::Utc> = sqlx::query_scalar("select now()")
+        .fetch_one(&mut *tx)
+        .await
+        .expect("now");
+    let installation_updated_at: chrono::DateTime<chrono::Utc> = sqlx::query_scalar(
+        "update github_installations set account_login = 'org-renamed' where installation_id = $1 returning updated_at",
+    )
+    .bind(installation_id)
+    .fetch_one(&mut *tx)
+    .await
+    .expect("rename installation");
+    // An explicit stale value from the writer is overridden as well.
+    let action_updated_at: chrono::DateTime<chrono::Utc> = sqlx::query_scalar(
+        "update bot_actions set status = 'DONE', updated_at = now() - interval '1 day' where id = $1 returning updated_at",
+    )
+    .bind(action_id)
+    .fetch_one(&mut *tx)
+    .await
+    .expect("complete action");
+    tx.commit().await.expect("commit");
+
+    assert_eq!(installation_updated_at, tx_now);
+    assert_eq!(action_updated_at, tx_now);
+}
@@ -15,6 +15,7 @@ export type AppConfig = {
   backendInternalHmacSecret: string;
   githubAppId: string;
   githubAppPrivateKey: string;
+  githubVerifyInstallations: boolean;
   workerId: string;
   outboxPollingEnabled: boolean;
   outboxPollIntervalMs: number;
@@ -53,6 +54,7 @@ export const readConfig = (): AppConfig => {
     ),
     githubAppId: must(process.env.GITHUB_APP_ID, "GITHUB_APP_ID"),
     githubAppPrivateKey: must(process.env.GITHUB_APP_PRIVATE_KEY, "GITHUB_APP_PRIVATE_KEY").replace(/\\n/g, "\n"),
+    githubVerifyInstallations: process.env.GITHUB_VERIFY_INSTALLATIONS === "true",
     workerId: process.env.WORKER_ID ?? `bot-worker-${process.pid}`,
     outboxPollingEnabled: process.env.OUTBOX_POLLING_ENABLED !== "false",
     outboxPollIntervalMs,
@@ -22,6 +22,10 @@ type InstallationRepositoriesResponse = {
   total_count?: number;
 };
 
+type AppInstallationRecord = {
+  id?: number;
+};
+
 type InstallationRepositoryRef = {
   id: number;
   full_name: string;
@@ -111,6 +115,40 @@ export class GitHubClient {
     return repositories;
   }
 
+  async listAppInstallationIds(): Promise<Set<number>> {
+    const jwt = signGitHubAppJwt(this.appId, this.privateKeyPem);
+    const installationIds = new Set<number>();
+    let page = 1;
+
+    while (page <= 10) {
+      const res = await fetchWithRetry(`${this.apiBaseUrl}/app/installations?per_page=100&page=${page}`, {
+        method: "GET",
+        headers: {
+          accept: "application/vnd.github+json",
+          authorization: `Bearer ${jwt}`,
+          "x-github-api-version": "2022-11-28",
+        },
+      });
+      if (!res.ok) {
+        throw new Error(`GitHub list app installations failed (${res.status})`);
+      }
+
+      const chunk = (await res.json()) as AppInstallationRecord[];
+      for (const installation of chunk) {
+        if (typeof installation.id === "number") {
+          installationIds.add(installation.id);
+        }
+      }
+
+      if (chunk.length < 100) {
+        break;
+      }
+      page += 1;
+    }
+
+    return installationIds;
+  }
+
   private async upsertIssueComment(
     installationId: number,
     repoFullName: string,
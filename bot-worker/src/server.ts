@@ -18,6 +18,7 @@ type MetricsStore = {
   webhookIgnoredTotal: number;
   webhookPullRequestForwardedTotal: number;
   webhookInstallationSyncForwardedTotal: number;
+  webhookInstallationUnknownTotal: number;
   webhookIngestAcceptedTotal: number;
   webhookIngestDuplicateTotal: number;
   webhookIngestIgnoredTotal: number;
@@ -262,6 +263,16 @@ const withInstallationRepositories = async (
   };
 };
 
+const isKnownInstallation = async (ctx: AppContext, event: NormalizedInstallationSyncEvent): Promise<boolean> => {
+  if (!ctx.config.githubVerifyInstallations) {
+    return true;
+  }
+  const installationIds = await ctx.github.listAppInstallationIds();
+  const listed = installationIds.has(event.installation.id);
+  // GitHub drops the installation from the App listing before delivering `deleted`.
+  return event.event_name === "installation" && event.action === "deleted" ? !listed : listed;
+};
+
 const handleWebhook = async (ctx: AppContext, req: IncomingMessage, res: ServerResponse): Promise<void> => {
   const rawBody = await readBody(req);
   const event = parseGitHubWebhookEvent(req.headers, rawBody, ctx.config.githubWebhookSecret);
@@ -287,6 +298,18 @@ const handleWebhook = async (ctx: AppContext, req: IncomingMessage, res: ServerR
     return;
   }
 
+  if (!(await isKnownInstallation(ctx, event.payload))) {
+    ctx.metrics.webhookInstallationUnknownTotal += 1;
+    log("error", "webhook.installation_unknown", {
+      delivery_id: event.payload.delivery_id,
+      event_name: event.payload.event_name,
+      action: event.payload.action,
+      installation_id: event.payload.installation.id,
+    });
+    json(res, 403, { error: "unknown_installation" });
+    return;
+  }
+
   ctx.metrics.webhookInstallationSyncForwardedTotal += 1;
   const enriched = await withInstallationRepositories(ctx, event.payload);
   const response = await ctx.backend.postInstallationSyncEvent(enriched);
@@ -320,6 +343,7 @@ export const createAppServer = (config: AppConfig) => {
     webhookIgnoredTotal: 0,
     webhookPullRequestForwardedTotal: 0,
     webhookInstallationSyncForwardedTotal: 0,
+    webhookInstallationUnknownTotal: 0,
     webhookIngestAcceptedTotal: 0,
     webhookIngestDuplicateTotal: 0,
     webhookIngestIgnoredTotal: 0,
@@ -397,6 +421,8 @@ export const createAppServer = (config: AppConfig) => {
             `sitg_bot_webhook_pull_request_forwarded_total ${appCtx.metrics.webhookPullRequestForwardedTotal}`,
             "# TYPE sitg_bot_webhook_installation_sync_forwarded_total counter",
             `sitg_bot_webhook_installation_sync_forwarded_total ${appCtx.metrics.webhookInstallationSyncForwardedTotal}`,
+            "# TYPE sitg_bot_webhook_installation_unknown_total counter",
+            `sitg_bot_webhook_installation_unknown_total ${appCtx.metrics.webhookInstallationUnknownTotal}`,
             "# TYPE sitg_bot_webhook_ingest_accepted_total counter",
             `sitg_bot_webhook_ingest_accepted_total ${appCtx.metrics.webhookIngestAcceptedTotal}`,
             "# TYPE sitg_bot_webhook_ingest_duplicate_total counter",
@@ -24,13 +24,15 @@ export type NormalizedPrEvent = {
 
 export type InstallationAccountType = "User" | "Organization";
 
-export type InstallationEventName = "installation" | "installation_repositories";
+export type InstallationEventName = "installation" | "installation_repositories" | "repository";
 
 export type InstallationAction = "created" | "deleted" | "suspend" | "unsuspend";
 
 export type InstallationRepositoriesAction = "added" | "removed";
 
-export type InstallationSyncAction = InstallationAction | InstallationRepositoriesAction;
+export type RepositoryAction = "renamed" | "transferred";
+
+export type InstallationSyncAction = InstallationAction | InstallationRepositoriesAction | RepositoryAction;
 
 export type InstallationRepositoryRef = {
   id: number;
@@ -58,6 +60,8 @@ export type PullRequestIngestResponse = {
   ingest_status: IngestStatus;
   challenge_id: string | null;
   enqueued_actions: number;
+  ignored_reason?: string;
+  draft_gated?: boolean;
 };
 
 export type InstallationSyncIngestResponse = {
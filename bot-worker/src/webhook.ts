@@ -9,11 +9,13 @@ import type {
   NormalizedInstallationSyncEvent,
   NormalizedPrEvent,
   PrAction,
+  RepositoryAction,
 } from "./types.js";
 
 const SUPPORTED_PR_ACTIONS = new Set<PrAction>(["opened", "reopened", "synchronize"]);
 const SUPPORTED_INSTALLATION_ACTIONS = new Set<InstallationAction>(["created", "deleted", "suspend", "unsuspend"]);
 const SUPPORTED_INSTALLATION_REPO_ACTIONS = new Set<InstallationRepositoriesAction>(["added", "removed"]);
+const SUPPORTED_REPOSITORY_ACTIONS = new Set<RepositoryAction>(["renamed", "transferred"]);
 const SUPPORTED_ACCOUNT_TYPES = new Set<InstallationAccountType>(["User", "Organization"]);
 
 type PullRequestPayload = {
@@ -44,6 +46,16 @@ type InstallationPayload = {
   repositories?: Array<{ id?: number; full_name?: string }>;
 };
 
+type RepositoryPayload = {
+  action?: string;
+  installation?: { id?: number };
+  repository?: {
+    id?: number;
+    full_name?: string;
+    owner?: { login?: string; type?: string };
+  };
+};
+
 export type NormalizedGitHubWebhookEvent =
   | {
       event_name: "pull_request";
@@ -210,6 +222,56 @@ const parseInstallationSyncEvent = (
   };
 };
 
+// App webhooks carry only the installation id, so the account is taken from the repo's (new) owner.
+const parseRepositorySyncEvent = (
+  deliveryId: string,
+  rawBody: Buffer,
+  nowIso: string,
+): NormalizedInstallationSyncEvent | null => {
+  let payload: RepositoryPayload;
+  try {
+    payload = JSON.parse(rawBody.toString("utf8")) as RepositoryPayload;
+  } catch {
+    return null;
+  }
+
+  if (!payload.action || !SUPPORTED_REPOSITORY_ACTIONS.has(payload.action as RepositoryAction)) {
+    return null;
+  }
+
+  const installationId = payload.installation?.id;
+  const repoId = payload.repository?.id;
+  const repoFullName = payload.repository?.full_name;
+  const ownerLogin = payload.repository?.owner?.login;
+  const ownerType = payload.repository?.owner?.type;
+
+  if (
+    typeof installationId !== "number" ||
+    typeof repoId !== "number" ||
+    typeof repoFullName !== "string" ||
+    typeof ownerLogin !== "string" ||
+    typeof ownerType !== "string" ||
+    !SUPPORTED_ACCOUNT_TYPES.has(ownerType as InstallationAccountType)
+  ) {
+    return null;
+  }
+
+  return {
+    delivery_id: deliveryId,
+    event_time: nowIso,
+    event_name: "repository",
+    action: payload.action as RepositoryAction,
+    installation: {
+      id: installationId,
+      account_login: ownerLogin,
+      account_type: ownerType as InstallationAccountType,
+    },
+    repositories_added: [],
+    repositories_removed: [],
+    repositories: [{ id: repoId, full_name: repoFullName }],
+  };
+};
+
 export const parseGitHubWebhookEvent = (
   headers: IncomingHttpHeaders,
   rawBody: Buffer,
@@ -243,5 +305,13 @@ export const parseGitHubWebhookEvent = (
     return { event_name: eventType, payload };
   }
 
+  if (eventType === "repository") {
+    const payload = parseRepositorySyncEvent(deliveryId, rawBody, nowIso);
+    if (!payload) {
+      return null;
+    }
+    return { event_name: eventType, payload };
+  }
+
   return null;
 };
@@ -26,6 +26,7 @@ const makeConfig = (): AppConfig => {
     backendInternalHmacSecret: "internal-hmac-secret",
     githubAppId: "12345",
     githubAppPrivateKey: privateKeyPem,
+    githubVerifyInstallations: false,
     workerId: "bot-worker-1",
     outboxPollingEnabled: false,
     outboxPollIntervalMs: 5_000,
@@ -296,6 +297,106 @@ test("installation created backfills repositories from GitHub when payload has n
   }
 });
 
+const sendInstallationRepositoriesWebhook = async (config: AppConfig, installationId: number): Promise<Response> => {
+  const server = createAppServer(config);
+  await new Promise<void>((resolve) => server.listen(0, resolve));
+  const port = (server.address() as AddressInfo).port;
+
+  try {
+    const payload = {
+      action: "added",
+      installation: {
+        id: installationId,
+        account: {
+          login: "org",
+          type: "Organization",
+        },
+      },
+      repositories_added: [{ id: 999, full_name: "org/repo" }],
+      repositories_removed: [],
+    };
+    const webhook = signedWebhook(payload, config.githubWebhookSecret);
+
+    const res = await fetch(`http://127.0.0.1:${port}/webhooks/github`, {
+      method: "POST",
+      headers: {
+        "content-type": "application/json",
+        "x-github-event": "installation_repositories",
+        "x-github-delivery": "00000000-0000-0000-0000-000000000777",
+        "x-hub-signature-256": webhook.signature,
+      },
+      body: webhook.body,
+    });
+    await res.arrayBuffer();
+    return res;
+  } finally {
+    server.close();
+  }
+};
+
+test("installation webhook for a known app installation forwards when verification is enabled", async () => {
+  const config: AppConfig = { ...makeConfig(), githubVerifyInstallations: true };
+  const calls: FetchCall[] = [];
+
+  const restoreFetch = installFetchMock(async (url, init) => {
+    const method = (init.method ?? "GET").toUpperCase();
+    const headers = new Headers(init.headers);
+    calls.push({ url, method, headers });
+
+    if (url === "https://api.github.com/app/installations?per_page=100&page=1" && method === "GET") {
+      assert.match(headers.get("authorization") ?? "", /^Bearer /);
+      return new Response(JSON.stringify([{ id: 111 }, { id: 333 }]), {
+        status: 200,
+        headers: { "content-type": "application/json" },
+      });
+    }
+
+    if (url === "http://backend.local/internal/v2/github/events/installation-sync" && method === "POST") {
+      return new Response(
+        JSON.stringify({ ingest_status: "ACCEPTED", updated_installation_id: 333, updated_repositories: 1 }),
+        { status: 200, headers: { "content-type": "application/json" } },
+      );
+    }
+
+    throw new Error(`Unexpected fetch: ${method} ${url}`);
+  });
+
+  try {
+    const res = await sendInstallationRepositoriesWebhook(config, 333);
+    assert.equal(res.status, 200);
+    assert.ok(calls.some((c) => c.url === "http://backend.local/internal/v2/github/events/installation-sync"));
+  } finally {
+    restoreFetch();
+  }
+});
+
+test("installation webhook for an unknown app installation is rejected without syncing", async () => {
+  const config: AppConfig = { ...makeConfig(), githubVerifyInstallations: true };
+  const calls: FetchCall[] = [];
+
+  const restoreFetch = installFetchMock(async (url, init) => {
+    const method = (init.method ?? "GET").toUpperCase();
+    calls.push({ url, method, headers: new Headers(init.headers) });
+
+    if (url === "https://api.github.com/app/installations?per_page=100&page=1" && method === "GET") {
+      return new Response(JSON.stringify([{ id: 111 }]), {
+        status: 200,
+        headers: { "content-type": "application/json" },
+      });
+    }
+
+    throw new Error(`Unexpected fetch: ${method} ${url}`);
+  });
+
+  try {
+    const res = await sendInstallationRepositoriesWebhook(config, 333);
+    assert.equal(res.status, 403);
+    assert.ok(!calls.some((c) => c.url.startsWith("http://backend.local/")));
+  } finally {
+    restoreFetch();
+  }
+});
+
 test("outbox polling executes UPSERT_PR_COMMENT and acks SUCCEEDED", async () => {
   const config: AppConfig = {
     ...makeConfig(),
@@ -73,6 +73,32 @@ test("parseGitHubWebhookEvent returns normalized installation_repositories event
   assert.equal(parsed.payload.repositories_added[0]?.full_name, "org/repo");
 });
 
+test("parseGitHubWebhookEvent returns normalized repository rename event", () => {
+  const payload = {
+    action: "renamed",
+    installation: { id: 123 },
+    repository: {
+      id: 456,
+      full_name: "org/renamed",
+      owner: { login: "org", type: "Organization" },
+    },
+    changes: { repository: { name: { from: "repo" } } },
+  };
+
+  const { raw, headers, secret } = makeSignedEvent("repository", "delivery-3", payload);
+  const parsed = parseGitHubWebhookEvent(headers, raw, secret, "2026-02-13T00:00:00.000Z");
+
+  assert.ok(parsed);
+  if (!parsed || parsed.event_name !== "repository") {
+    throw new Error("expected repository event");
+  }
+
+  assert.equal(parsed.payload.action, "renamed");
+  assert.equal(parsed.payload.installation.id, 123);
+  assert.equal(parsed.payload.installation.account_login, "org");
+  assert.deepEqual(parsed.payload.repositories, [{ id: 456, full_name: "org/renamed" }]);
+});
+
 test("parseGitHubWebhookEvent ignores unsupported action", () => {
   const payload = {
     action: "closed",
@@ -85,6 +85,7 @@ describe('api client', () => {
           usd_estimate: '300',
           input_mode: 'ETH',
           input_value: '0.1',
+          currency: 'USD',
           spot_price_usd: '3000',
           spot_source: 'coingecko',
           spot_at: '2026-02-13T00:00:00Z',
@@ -4,8 +4,11 @@ import type {
   ConfirmResponse,
   ConfirmTypedDataResponse,
   GateResponse,
+  FiatCurrency,
+  InputMode,
   InstallStatusResponse,
   MeResponse,
+  PublicConfigResponse,
   RepoConfigResponse,
   RepoOption,
   ResolveLoginsResponse,
@@ -13,7 +16,8 @@ import type {
   WalletLinkChallengeResponse,
   WalletLinkConfirmResponse,
   WalletLinkStatusResponse,
-  WhitelistEntry
+  WhitelistEntry,
+  WhitelistPutResponse
 } from './types';
 
 const JSON_HEADERS = { 'Content-Type': 'application/json' };
@@ -36,10 +40,16 @@ function normalizeErrorCode(rawCode: string | undefined, message: string): strin
   return rawCode;
 }
 
-function makeApiError(message: string, status?: number, code?: string): ApiError {
+function makeApiError(
+  message: string,
+  status?: number,
+  code?: string,
+  details?: Record<string, unknown>
+): ApiError {
   const err = new Error(message) as ApiError;
   err.status = status;
   err.code = normalizeErrorCode(code, message);
+  err.details = details;
   return err;
 }
 
@@ -54,7 +64,8 @@ async function request<T>(path: string, init?: RequestInit): Promise<T> {
     throw makeApiError(
       payload?.error.message ?? `Request failed (${response.status})`,
       response.status,
-      payload?.error.code
+      payload?.error.code,
+      payload?.error.details
     );
   }
 
@@ -120,13 +131,22 @@ export async function logout(): Promise<void> {
   await request('/api/v1/auth/logout', { method: 'POST' });
 }
 
+export function getPublicConfig(): Promise<PublicConfigResponse> {
+  return request<PublicConfigResponse>('/api/v1/config/public');
+}
+
 export function getRepoConfig(repoId: string): Promise<RepoConfigResponse> {
   return request<RepoConfigResponse>(`/api/v1/repos/${repoId}/config`);
 }
 
 export function putRepoConfig(
   repoId: string,
-  payload: { input_mode: 'ETH' | 'USD'; input_value: string; draft_prs_gated: boolean }
+  payload: {
+    input_mode: InputMode;
+    input_value: string;
+    currency?: FiatCurrency;
+    draft_prs_gated: boolean;
+  }
 ): Promise<RepoConfigResponse> {
   return request<RepoConfigResponse>(`/api/v1/repos/${repoId}/config`, {
     method: 'PUT',
@@ -151,8 +171,8 @@ export function resolveWhitelistLogins(repoId: string, logins: string[]): Promis
   });
 }
 
-export function putWhitelist(repoId: string, entries: WhitelistEntry[]): Promise<void> {
-  return request<void>(`/api/v1/repos/${repoId}/whitelist`, {
+export function putWhitelist(repoId: string, entries: WhitelistEntry[]): Promise<WhitelistPutResponse> {
+  return request<WhitelistPutResponse>(`/api/v1/repos/${repoId}/whitelist`, {
     method: 'PUT',
     headers: JSON_HEADERS,
     body: JSON.stringify({ entries })
@@ -167,11 +187,15 @@ export function getConfirmTypedData(gateToken: string): Promise<ConfirmTypedData
   return request<ConfirmTypedDataResponse>(`/api/v1/gate/${gateToken}/confirm-typed-data`);
 }
 
-export function submitGateConfirmation(gateToken: string, signature: string): Promise<ConfirmResponse> {
+export function submitGateConfirmation(
+  gateToken: string,
+  signature: string,
+  expiresAt?: number
+): Promise<ConfirmResponse> {
   return request<ConfirmResponse>(`/api/v1/gate/${gateToken}/confirm`, {
     method: 'POST',
     headers: JSON_HEADERS,
-    body: JSON.stringify({ signature })
+    body: JSON.stringify({ signature, expires_at: expiresAt })
   });
 }
 
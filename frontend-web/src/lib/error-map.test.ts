@@ -6,6 +6,31 @@ describe('toUserMessage', () => {
     expect(toUserMessage({ code: 'WALLET_HAS_STAKE', message: 'x' })).toContain('stake');
   });
 
+  it('gives distinct guidance for each wallet link conflict', () => {
+    const codes = [
+      'WALLET_LINK_CHALLENGE_INVALID',
+      'SIGNER_MISMATCH',
+      'WALLET_ALREADY_LINKED',
+      'CHAIN_ID_MISMATCH',
+      'WALLET_IDENTITY_REQUIRED'
+    ];
+    const messages = codes.map((code) => toUserMessage({ code, message: 'conflict: x' }));
+    expect(new Set(messages).size).toBe(codes.length);
+    expect(messages).not.toContain('conflict: x');
+  });
+
+  it('mentions the last known quote when prices are unavailable', () => {
+    const message = toUserMessage({
+      code: 'PRICE_UNAVAILABLE',
+      message: 'price unavailable',
+      details: { last_quote_at: '2026-02-13T00:00:00Z' }
+    });
+    expect(message).toContain('2026-02-13T00:00:00Z');
+    expect(toUserMessage({ code: 'PRICE_UNAVAILABLE', details: { last_quote_at: null } })).toBe(
+      'Price quote unavailable. Try again shortly.'
+    );
+  });
+
   it('falls back to raw message', () => {
     expect(toUserMessage({ message: 'raw problem' })).toBe('raw problem');
   });
@@ -9,7 +9,10 @@ const CODE_MESSAGES: Record<string, string> = {
   CHALLENGE_NOT_PENDING: 'Challenge is no longer pending.',
   WALLET_LINK_CHALLENGE_INVALID: 'Wallet link challenge expired or already used. Retry linking.',
   WALLET_ALREADY_LINKED: 'This wallet is already linked to another GitHub account.',
+  WALLET_IDENTITY_REQUIRED: 'This wallet needs a primary name (e.g. ENS) before it can be linked.',
+  SIGNER_MISMATCH: 'The signature does not match the wallet. Sign with the wallet you are linking.',
   NONCE_INVALID: 'Confirmation nonce is invalid or expired. Reload and retry.',
+  CHAIN_ID_MISMATCH: 'Your wallet signed on the wrong network. Switch to Base and retry.',
   VALIDATION_ERROR: 'Please check your inputs and try again.'
 };
 
@@ -23,6 +26,10 @@ export function toUserMessage(error: unknown): string {
     ) {
       return 'GitHub App is not installed for this repo owner yet. Install it, then try again.';
     }
+    const lastQuoteAt = apiError.details?.last_quote_at;
+    if (apiError.code === 'PRICE_UNAVAILABLE' && typeof lastQuoteAt === 'string') {
+      return `Prices are temporarily unavailable (last known quote at ${lastQuoteAt}). Try again shortly.`;
+    }
     if (apiError.code && CODE_MESSAGES[apiError.code]) {
       return CODE_MESSAGES[apiError.code];
     }
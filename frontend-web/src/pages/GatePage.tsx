@@ -478,7 +478,7 @@ export function GatePage() {
         message: normalized.message
       });
 
-      return submitGateConfirmation(gateToken, signature);
+      return submitGateConfirmation(gateToken, signature, Number(typed.message.expiresAt));
     });
 
     if (!confirmed) {
@@ -564,7 +564,7 @@ export function GatePage() {
 
         <dl className="kv">
           <dt>Author</dt>
-          <dd>@{gate.github_pr_author_login}</dd>
+          <dd>{gate.github_pr_author_login ? `@${gate.github_pr_author_login}` : 'Sign in to view'}</dd>
           <dt>Head SHA</dt>
           <dd>{gate.head_sha.slice(0, 12)}</dd>
           <dt>Threshold (wei)</dt>
@@ -143,11 +143,11 @@ export function OwnerPage() {
   const summary = useMemo(() => {
     if (!config) {
       return {
-        enforcedEth: configForm.inputMode === 'ETH' ? configForm.inputValue : 'pending',
+        enforcedEth: configForm.inputMode === 'USD' ? 'pending' : configForm.inputValue,
         usdEstimate: 'pending'
       };
     }
-    return { enforcedEth: config.threshold.eth, usdEstimate: config.threshold.usd_estimate };
+    return { enforcedEth: config.threshold.eth, usdEstimate: config.threshold.usd_estimate ?? 'n/a' };
   }, [config, configForm]);
 
   const handleSaveConfig = async (event: FormEvent<HTMLFormElement>): Promise<void> => {
@@ -187,7 +187,7 @@ describe('GatePage confirmation flow', () => {
     await waitFor(() => {
       expect(apiMocks.getConfirmTypedData).toHaveBeenCalledWith('token-1');
       expect(wagmiMocks.signTypedDataAsync).toHaveBeenCalledTimes(1);
-      expect(apiMocks.submitGateConfirmation).toHaveBeenCalledWith('token-1', '0xconfirm-sig');
+      expect(apiMocks.submitGateConfirmation).toHaveBeenCalledWith('token-1', '0xconfirm-sig', 4070908800);
       expect(apiMocks.getGate).toHaveBeenCalledTimes(2);
     });
   });
@@ -69,6 +69,7 @@ describe('OwnerPage flow', () => {
         usd_estimate: '2500',
         input_mode: 'ETH',
         input_value: '1',
+        currency: 'USD',
         spot_price_usd: '2500',
         spot_source: 'coingecko',
         spot_at: '2026-02-13T00:00:00Z',
@@ -93,6 +94,7 @@ describe('OwnerPage flow', () => {
         usd_estimate: '300',
         input_mode: 'ETH',
         input_value: '0.12',
+        currency: 'USD',
         spot_price_usd: '2500',
         spot_source: 'coingecko',
         spot_at: '2026-02-13T00:00:00Z',
@@ -106,7 +108,10 @@ describe('OwnerPage flow', () => {
       resolved: [{ github_user_id: 3003, github_login: 'alice' }],
       unresolved: ['ghost']
     });
-    apiMocks.putWhitelist.mockResolvedValue(undefined);
+    apiMocks.putWhitelist.mockResolvedValue({
+      inserted: [{ github_user_id: 3003, github_login: 'alice' }],
+      skipped: []
+    });
     apiMocks.logout.mockResolvedValue(undefined);
   });
 
@@ -74,6 +74,7 @@ export function ThresholdWhitelistTab({
                 >
                   <option value="ETH">ETH</option>
                   <option value="USD">USD</option>
+                  <option value="TOKEN">Token units</option>
                 </select>
               </label>
               <label>
@@ -1,15 +1,18 @@
-export type InputMode = 'ETH' | 'USD';
+export type InputMode = 'ETH' | 'USD' | 'TOKEN' | 'FIAT';
+export type FiatCurrency = 'USD' | 'EUR' | 'GBP';
 
 export interface ApiErrorBody {
   error: {
     code: string;
     message: string;
+    details?: Record<string, unknown>;
   };
 }
 
 export interface ApiError extends Error {
   code?: string;
   status?: number;
+  details?: Record<string, unknown>;
 }
 
 export interface MeResponse {
@@ -21,12 +24,13 @@ export interface MeResponse {
 export interface RepoThreshold {
   wei: string;
   eth: string;
-  usd_estimate: string;
+  usd_estimate: string | null;
   input_mode: InputMode;
   input_value: string;
-  spot_price_usd: string;
-  spot_source: string;
-  spot_at: string;
+  currency: FiatCurrency | null;
+  spot_price_usd: string | null;
+  spot_source: string | null;
+  spot_at: string | null;
   spot_from_cache: boolean;
   spot_quote_id: string | null;
   message: string;
@@ -36,6 +40,7 @@ export interface RepoConfigResponse {
   github_repo_id: number;
   threshold: RepoThreshold;
   draft_prs_gated: boolean;
+  archived_at?: string | null;
 }
 
 export interface RepoOption {
@@ -57,6 +62,15 @@ export interface WhitelistEntry {
   github_login: string;
 }
 
+export interface SkippedWhitelistEntry extends WhitelistEntry {
+  reason: 'INVALID_GITHUB_USER_ID' | 'GITHUB_USER_NOT_FOUND';
+}
+
+export interface WhitelistPutResponse {
+  inserted: WhitelistEntry[];
+  skipped: SkippedWhitelistEntry[];
+}
+
 export interface ResolveLoginsResponse {
   resolved: WhitelistEntry[];
   unresolved: string[];
@@ -68,8 +82,8 @@ export interface GateResponse {
   github_repo_id: number;
   github_repo_full_name: string;
   github_pr_number: number;
-  github_pr_author_id: number;
-  github_pr_author_login: string;
+  github_pr_author_id: number | null;
+  github_pr_author_login: string | null;
   head_sha: string;
   deadline_at: string;
   threshold_wei_snapshot: string;
@@ -94,6 +108,10 @@ export interface ConfirmTypedDataResponse {
     nonce: string;
     expiresAt: number;
   };
+  nonce_display?: {
+    decimal: string;
+    hex: string;
+  };
 }
 
 export interface ConfirmResponse {
@@ -117,8 +135,15 @@ export interface WalletLinkStatusResponse {
   linked_at: string;
 }
 
+export interface PublicConfigResponse {
+  chain_id: number;
+  staking_contract_address: string | null;
+  app_base_url: string;
+  gate_path_prefix: string;
+}
+
 export interface StakeStatusResponse {
   staked_balance_wei: string;
-  unlock_time: string;
+  unlock_time: string | null;
   lock_active: boolean;
 }